@@ -0,0 +1,540 @@
+//! Syntax tree of an expression
+//!
+//! Produced by [`crate::Parser`] and computed by [`crate::Evaluator::eval`].
+//! Every node remembers where it came from in the expression
+//! so errors found while computing can point back at the source.
+
+use crate::syntax::is_word_char;
+use crate::{Operator, Span, Symbol, Syntax, UnaryOperator};
+use std::{fmt, mem};
+
+/// Node of the syntax tree
+///
+/// Cloning, comparing, printing and dropping walk the tree without recursion,
+/// so however long an expression is, they do not overflow the call stack
+pub enum Expr<N = i128> {
+    /// Number literal
+    Num { value: N, span: Span },
+    /// Value of a variable
+    Var { name: String, span: Span },
+    /// `name = value`
+    /// Evaluates to the assigned value
+    Assign {
+        name: String,
+        name_span: Span,
+        /// Where assignment symbol appeared in the expression
+        op_span: Span,
+        value: Box<Expr<N>>,
+    },
+    /// `lhs op rhs`
+    BinOp {
+        op: Operator,
+        /// Where operator appeared in the expression
+        op_span: Span,
+        lhs: Box<Expr<N>>,
+        rhs: Box<Expr<N>>,
+    },
+    /// Parenthesized expression
+    /// `span` covers both parenthesis
+    Group { inner: Box<Expr<N>>, span: Span },
+    /// Negation of `operand`
+    /// `op_span` is where the minus sign appeared
+    Neg {
+        operand: Box<Expr<N>>,
+        op_span: Span,
+    },
+    /// `op operand`
+    Unary {
+        op: UnaryOperator,
+        op_span: Span,
+        operand: Box<Expr<N>>,
+    },
+    /// `condition ? then : otherwise`
+    /// Only one of `then` and `otherwise` is computed
+    Conditional {
+        condition: Box<Expr<N>>,
+        then: Box<Expr<N>>,
+        otherwise: Box<Expr<N>>,
+    },
+    /// `name(args...)`
+    /// `span` covers the name and both parenthesis
+    Call {
+        name: String,
+        name_span: Span,
+        args: Vec<Expr<N>>,
+        span: Span,
+    },
+}
+
+impl<N> Expr<N> {
+    /// Part of the expression this node was parsed from
+    ///
+    /// Found by following leftmost and rightmost children
+    /// without recursion, however deep the tree is
+    pub fn span(&self) -> Span {
+        let mut first = self;
+        let start = loop {
+            match first {
+                Expr::Num { span, .. }
+                | Expr::Var { span, .. }
+                | Expr::Group { span, .. }
+                | Expr::Call { span, .. } => break span.start,
+                Expr::Assign { name_span, .. } => break name_span.start,
+                Expr::Neg { op_span, .. } | Expr::Unary { op_span, .. } => break op_span.start,
+                Expr::BinOp { lhs, .. } => first = lhs,
+                Expr::Conditional { condition, .. } => first = condition,
+            }
+        };
+        let mut last = self;
+        let end = loop {
+            match last {
+                Expr::Num { span, .. }
+                | Expr::Var { span, .. }
+                | Expr::Group { span, .. }
+                | Expr::Call { span, .. } => break span.end,
+                Expr::Assign { value: child, .. }
+                | Expr::Neg { operand: child, .. }
+                | Expr::Unary { operand: child, .. }
+                | Expr::BinOp { rhs: child, .. }
+                | Expr::Conditional {
+                    otherwise: child, ..
+                } => last = child,
+            }
+        };
+        Span::new(start, end)
+    }
+}
+
+/// Dropping nested boxes recursively overflows the call stack
+/// on long expressions, so children are moved out and dropped one by one
+impl<N> Drop for Expr<N> {
+    fn drop(&mut self) {
+        let mut children = vec![];
+        self.take_children(&mut children);
+        while let Some(mut child) = children.pop() {
+            child.take_children(&mut children);
+        }
+    }
+}
+
+impl<N> Expr<N> {
+    /// Move children of this node into `children` leaving cheap leaves in place
+    fn take_children(&mut self, children: &mut Vec<Expr<N>>) {
+        let mut take = |expr: &mut Box<Expr<N>>| {
+            let leaf = Expr::Var {
+                name: String::new(),
+                span: Span::default(),
+            };
+            children.push(mem::replace(expr.as_mut(), leaf));
+        };
+        match self {
+            Expr::Num { .. } | Expr::Var { .. } => {}
+            Expr::Group { inner: child, .. }
+            | Expr::Neg { operand: child, .. }
+            | Expr::Unary { operand: child, .. }
+            | Expr::Assign { value: child, .. } => take(child),
+            Expr::BinOp { lhs, rhs, .. } => {
+                take(lhs);
+                take(rhs);
+            }
+            Expr::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => {
+                take(condition);
+                take(then);
+                take(otherwise);
+            }
+            Expr::Call { args, .. } => children.append(args),
+        }
+    }
+
+    /// Children of this node from left to right
+    fn children(&self) -> Vec<&Expr<N>> {
+        match self {
+            Expr::Num { .. } | Expr::Var { .. } => vec![],
+            Expr::Group { inner: child, .. }
+            | Expr::Neg { operand: child, .. }
+            | Expr::Unary { operand: child, .. }
+            | Expr::Assign { value: child, .. } => vec![child],
+            Expr::BinOp { lhs, rhs, .. } => vec![lhs, rhs],
+            Expr::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => vec![condition, then, otherwise],
+            Expr::Call { args, .. } => args.iter().collect(),
+        }
+    }
+}
+
+impl<N: Clone> Expr<N> {
+    /// Copy of this node with `children` in place of its own, in the order of `children()`
+    fn with_children(&self, children: Vec<Expr<N>>) -> Expr<N> {
+        let mut children = children.into_iter();
+        let mut next = || Box::new(children.next().expect("child for every child"));
+        match self {
+            Expr::Num { value, span } => Expr::Num {
+                value: value.clone(),
+                span: *span,
+            },
+            Expr::Var { name, span } => Expr::Var {
+                name: name.clone(),
+                span: *span,
+            },
+            Expr::Assign {
+                name,
+                name_span,
+                op_span,
+                ..
+            } => Expr::Assign {
+                name: name.clone(),
+                name_span: *name_span,
+                op_span: *op_span,
+                value: next(),
+            },
+            Expr::BinOp { op, op_span, .. } => Expr::BinOp {
+                op: *op,
+                op_span: *op_span,
+                lhs: next(),
+                rhs: next(),
+            },
+            Expr::Group { span, .. } => Expr::Group {
+                inner: next(),
+                span: *span,
+            },
+            Expr::Neg { op_span, .. } => Expr::Neg {
+                operand: next(),
+                op_span: *op_span,
+            },
+            Expr::Unary { op, op_span, .. } => Expr::Unary {
+                op: *op,
+                op_span: *op_span,
+                operand: next(),
+            },
+            Expr::Conditional { .. } => Expr::Conditional {
+                condition: next(),
+                then: next(),
+                otherwise: next(),
+            },
+            Expr::Call {
+                name,
+                name_span,
+                span,
+                ..
+            } => Expr::Call {
+                name: name.clone(),
+                name_span: *name_span,
+                args: children.collect(),
+                span: *span,
+            },
+        }
+    }
+}
+
+impl<N: Clone> Clone for Expr<N> {
+    /// Children are copied before their parent, kept on a stack of finished nodes
+    fn clone(&self) -> Self {
+        enum Task<'a, N> {
+            Visit(&'a Expr<N>),
+            Build(&'a Expr<N>, usize),
+        }
+        let mut tasks = vec![Task::Visit(self)];
+        let mut built = vec![];
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(expr) => {
+                    let children = expr.children();
+                    tasks.push(Task::Build(expr, children.len()));
+                    tasks.extend(children.into_iter().rev().map(Task::Visit));
+                }
+                Task::Build(expr, count) => {
+                    let children = built.split_off(built.len() - count);
+                    built.push(expr.with_children(children));
+                }
+            }
+        }
+        built.pop().expect("root is built last")
+    }
+}
+
+impl<N: PartialEq> PartialEq for Expr<N> {
+    /// Nodes are compared pairwise, each without its children
+    fn eq(&self, other: &Self) -> bool {
+        let mut pairs = vec![(self, other)];
+        while let Some((lhs, rhs)) = pairs.pop() {
+            let same = match (lhs, rhs) {
+                (Expr::Num { value, span }, Expr::Num { value: v, span: s }) => {
+                    value == v && span == s
+                }
+                (Expr::Var { name, span }, Expr::Var { name: n, span: s }) => {
+                    name == n && span == s
+                }
+                (
+                    Expr::Assign {
+                        name,
+                        name_span,
+                        op_span,
+                        ..
+                    },
+                    Expr::Assign {
+                        name: n,
+                        name_span: ns,
+                        op_span: os,
+                        ..
+                    },
+                ) => name == n && name_span == ns && op_span == os,
+                (
+                    Expr::BinOp { op, op_span, .. },
+                    Expr::BinOp {
+                        op: o, op_span: os, ..
+                    },
+                ) => op == o && op_span == os,
+                (Expr::Group { span, .. }, Expr::Group { span: s, .. }) => span == s,
+                (Expr::Neg { op_span, .. }, Expr::Neg { op_span: os, .. }) => op_span == os,
+                (
+                    Expr::Unary { op, op_span, .. },
+                    Expr::Unary {
+                        op: o, op_span: os, ..
+                    },
+                ) => op == o && op_span == os,
+                (Expr::Conditional { .. }, Expr::Conditional { .. }) => true,
+                (
+                    Expr::Call {
+                        name,
+                        name_span,
+                        args,
+                        span,
+                    },
+                    Expr::Call {
+                        name: n,
+                        name_span: ns,
+                        args: a,
+                        span: s,
+                    },
+                ) => name == n && name_span == ns && args.len() == a.len() && span == s,
+                _ => false,
+            };
+            if !same {
+                return false;
+            }
+            pairs.extend(lhs.children().into_iter().zip(rhs.children()));
+        }
+        true
+    }
+}
+
+impl<N: Eq> Eq for Expr<N> {}
+
+/// Part of an expression being printed, either text or a node still to print
+enum Piece<'a, N> {
+    Text(String),
+    Expr(&'a Expr<N>),
+}
+
+/// Print `root` by replacing each node with pieces from `expand` until only text is left
+fn write_pieces<'a, N>(
+    f: &mut fmt::Formatter<'_>,
+    root: &'a Expr<N>,
+    expand: impl Fn(&'a Expr<N>) -> Vec<Piece<'a, N>>,
+) -> fmt::Result {
+    let mut pieces = vec![Piece::Expr(root)];
+    while let Some(piece) = pieces.pop() {
+        match piece {
+            Piece::Text(text) => f.write_str(&text)?,
+            Piece::Expr(expr) => pieces.extend(expand(expr).into_iter().rev()),
+        }
+    }
+    Ok(())
+}
+
+/// Same as a derived `Debug`, except that `{:#?}` is not spread over lines
+impl<N: fmt::Debug> fmt::Debug for Expr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = |text: String| Piece::Text(text);
+        write_pieces(f, self, |expr| match expr {
+            Expr::Num { value, span } => {
+                vec![text(format!("Num {{ value: {value:?}, span: {span:?} }}"))]
+            }
+            Expr::Var { name, span } => {
+                vec![text(format!("Var {{ name: {name:?}, span: {span:?} }}"))]
+            }
+            Expr::Assign {
+                name,
+                name_span,
+                op_span,
+                value,
+            } => vec![
+                text(format!(
+                    "Assign {{ name: {name:?}, name_span: {name_span:?}, "
+                )),
+                text(format!("op_span: {op_span:?}, value: ")),
+                Piece::Expr(value),
+                text(" }".to_string()),
+            ],
+            Expr::BinOp {
+                op,
+                op_span,
+                lhs,
+                rhs,
+            } => vec![
+                text(format!("BinOp {{ op: {op:?}, op_span: {op_span:?}, lhs: ")),
+                Piece::Expr(lhs),
+                text(", rhs: ".to_string()),
+                Piece::Expr(rhs),
+                text(" }".to_string()),
+            ],
+            Expr::Group { inner, span } => vec![
+                text("Group { inner: ".to_string()),
+                Piece::Expr(inner),
+                text(format!(", span: {span:?} }}")),
+            ],
+            Expr::Neg { operand, op_span } => vec![
+                text("Neg { operand: ".to_string()),
+                Piece::Expr(operand),
+                text(format!(", op_span: {op_span:?} }}")),
+            ],
+            Expr::Unary {
+                op,
+                op_span,
+                operand,
+            } => vec![
+                text(format!(
+                    "Unary {{ op: {op:?}, op_span: {op_span:?}, operand: "
+                )),
+                Piece::Expr(operand),
+                text(" }".to_string()),
+            ],
+            Expr::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => vec![
+                text("Conditional { condition: ".to_string()),
+                Piece::Expr(condition),
+                text(", then: ".to_string()),
+                Piece::Expr(then),
+                text(", otherwise: ".to_string()),
+                Piece::Expr(otherwise),
+                text(" }".to_string()),
+            ],
+            Expr::Call {
+                name,
+                name_span,
+                args,
+                span,
+            } => {
+                let mut pieces = vec![text(format!(
+                    "Call {{ name: {name:?}, name_span: {name_span:?}, args: ["
+                ))];
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        pieces.push(text(", ".to_string()));
+                    }
+                    pieces.push(Piece::Expr(arg));
+                }
+                pieces.push(text(format!("], span: {span:?} }}")));
+                pieces
+            }
+        })
+    }
+}
+
+/// Expression printed with symbols of a [`Syntax`]
+pub struct ExprDisplay<'a, N = i128> {
+    expr: &'a Expr<N>,
+    syntax: &'a Syntax,
+}
+
+impl<N> Expr<N> {
+    /// Print expression back with symbols of `syntax`
+    ///
+    /// Example:
+    /// `3ae4c66fb32` is printed as `3 a e4 c 66f b 32` with `Syntax::letters()`
+    /// and as `3 + (4 * 66) - 32` with `Syntax::standard()`
+    pub fn display<'a>(&'a self, syntax: &'a Syntax) -> ExprDisplay<'a, N> {
+        ExprDisplay { expr: self, syntax }
+    }
+}
+
+impl<N: fmt::Display> fmt::Display for ExprDisplay<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // symbol missing from syntax is printed with its debug name
+        let symbol = |symbol| match self.syntax.text_of(symbol) {
+            Some(text) => Piece::Text(text.to_string()),
+            None => Piece::Text(format!("{symbol:?}")),
+        };
+        let text = |text: &str| Piece::Text(text.to_string());
+
+        write_pieces(f, self.expr, |expr| match expr {
+            Expr::Num { value, .. } => vec![Piece::Text(value.to_string())],
+            Expr::Var { name, .. } => vec![text(name)],
+            Expr::Assign { name, value, .. } => vec![
+                text(name),
+                text(" "),
+                symbol(Symbol::Assign),
+                text(" "),
+                Piece::Expr(value),
+            ],
+            Expr::BinOp { op, lhs, rhs, .. } => vec![
+                Piece::Expr(lhs),
+                text(" "),
+                symbol(Symbol::Op(*op)),
+                text(" "),
+                Piece::Expr(rhs),
+            ],
+            Expr::Group { inner, .. } => vec![
+                symbol(Symbol::LParen),
+                Piece::Expr(inner),
+                symbol(Symbol::RParen),
+            ],
+            Expr::Neg { operand, .. } => {
+                vec![symbol(Symbol::Op(Operator::Sub)), Piece::Expr(operand)]
+            }
+            Expr::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => vec![
+                Piece::Expr(condition),
+                text(" "),
+                symbol(Symbol::Then),
+                text(" "),
+                Piece::Expr(then),
+                text(" "),
+                symbol(Symbol::Else),
+                text(" "),
+                Piece::Expr(otherwise),
+            ],
+            Expr::Unary { op, operand, .. } => {
+                vec![symbol(Symbol::Unary(*op)), Piece::Expr(operand)]
+            }
+            Expr::Call { name, args, .. } => {
+                let open = self.syntax.text_of(Symbol::LParen);
+                // keep name and a word parenthesis like `e` apart
+                let gap = match open {
+                    Some(open) if open.starts_with(is_word_char) => " ",
+                    _ => "",
+                };
+                let mut pieces = vec![text(name), text(gap), symbol(Symbol::LParen)];
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        pieces.push(symbol(Symbol::ArgSeparator));
+                        pieces.push(text(" "));
+                    }
+                    pieces.push(Piece::Expr(arg));
+                }
+                pieces.push(symbol(Symbol::RParen));
+                pieces
+            }
+        })
+    }
+}
+
+/// Print expression back in the default letter syntax
+impl<N: fmt::Display> fmt::Display for Expr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display(&Syntax::default()).fmt(f)
+    }
+}
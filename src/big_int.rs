@@ -0,0 +1,823 @@
+//! Integers of any size, limited only by memory
+//!
+//! Example:
+//! ```
+//! use parser_rs::{BigInt, EvalOptions, Evaluator};
+//!
+//! let evaluator = Evaluator::<BigInt>::with_backend(EvalOptions::new());
+//! let product = evaluator.evaluate("2i100 c 3").unwrap();
+//! assert_eq!(product.to_string(), "3802951800684688204490109616128");
+//! ```
+
+use crate::lexer::RADIX;
+use crate::{ArithmeticMode, ErrorKind, Number, Rounding};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Arbitrary-precision integer
+///
+/// Never overflows in any [`ArithmeticMode`]. Powers and shifts with results
+/// longer than `EvalOptions::max_bits`, too large to compute in reasonable time,
+/// fail with `ErrorKind::ResultTooLarge` instead.
+/// Bitwise operators act as on an infinitely sign-extended two's complement
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    /// Base 2^32 digits, least significant first, without leading zeros
+    magnitude: Vec<u32>,
+}
+
+const BASE_BITS: usize = 32;
+
+/// Largest power of 10 in a digit, used to convert from and to decimal
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 9;
+
+/// Longest result of a power or shift by default, about 79 000 decimal digits
+pub(crate) const MAX_BITS: usize = 1 << 18;
+
+impl BigInt {
+    /// Number of the given sign and magnitude. Zero is never negative
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        trim(&mut magnitude);
+        BigInt {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub(crate) fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.magnitude.clone())
+    }
+
+    pub(crate) fn negated(&self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+
+    pub(crate) fn is_odd(&self) -> bool {
+        self.magnitude.first().is_some_and(|digit| digit % 2 == 1)
+    }
+
+    /// Length of the magnitude in bits
+    fn bits(&self) -> usize {
+        match self.magnitude.last() {
+            Some(top) => self.magnitude.len() * BASE_BITS - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Magnitude as a `usize` if it fits
+    fn to_usize(&self) -> Option<usize> {
+        self.magnitude
+            .iter()
+            .rev()
+            .try_fold(0usize, |value, &digit| {
+                value
+                    .checked_mul(1 << BASE_BITS)?
+                    .checked_add(digit as usize)
+            })
+    }
+
+    /// `magnitude` as a non-negative number, also above `i128::MAX`
+    pub(crate) fn from_unsigned(mut magnitude: u128) -> BigInt {
+        let mut digits = vec![];
+        while magnitude > 0 {
+            digits.push(magnitude as u32);
+            magnitude >>= BASE_BITS;
+        }
+        BigInt::from_parts(false, digits)
+    }
+
+    /// Value as an `i128` if it fits
+    pub(crate) fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.to_u128()?;
+        match self.negative {
+            true if magnitude <= i128::MIN.unsigned_abs() => {
+                Some(0u128.wrapping_sub(magnitude) as i128)
+            }
+            false => i128::try_from(magnitude).ok(),
+            true => None,
+        }
+    }
+
+    /// Lowest 128 bits of the two's complement, as an `i128`
+    pub(crate) fn wrapping_i128(&self) -> i128 {
+        BigInt::from_parts(false, self.twos_complement(128 / BASE_BITS))
+            .to_u128()
+            .expect("at most 128 bits") as i128
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        self.magnitude
+            .iter()
+            .rev()
+            .try_fold(0u128, |value, &digit| {
+                value
+                    .checked_mul(1 << BASE_BITS)?
+                    .checked_add(digit as u128)
+            })
+    }
+
+    pub(crate) fn plus(&self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add(&self.magnitude, &rhs.magnitude));
+        }
+        // larger magnitude decides the sign
+        match compare(&self.magnitude, &rhs.magnitude) {
+            Ordering::Less => {
+                BigInt::from_parts(rhs.negative, sub(&rhs.magnitude, &self.magnitude))
+            }
+            _ => BigInt::from_parts(self.negative, sub(&self.magnitude, &rhs.magnitude)),
+        }
+    }
+
+    pub(crate) fn times(&self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            mul(&self.magnitude, &rhs.magnitude),
+        )
+    }
+
+    /// Quotient rounding toward zero and remainder with the sign of `self`
+    pub(crate) fn div_rem(&self, rhs: &BigInt) -> Result<(BigInt, BigInt), ErrorKind> {
+        if rhs.is_zero() {
+            return Err(ErrorKind::DivisionByZero);
+        }
+        let (quotient, remainder) = div_rem(&self.magnitude, &rhs.magnitude);
+        Ok((
+            BigInt::from_parts(self.negative != rhs.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        ))
+    }
+
+    /// Two's complement digits, sign extended to `len`
+    fn twos_complement(&self, len: usize) -> Vec<u32> {
+        // -x is !(x - 1)
+        let mut digits = match self.negative {
+            true => sub(&self.magnitude, &[1]),
+            false => self.magnitude.clone(),
+        };
+        digits.resize(len, 0);
+        if self.negative {
+            digits.iter_mut().for_each(|digit| *digit = !*digit);
+        }
+        digits
+    }
+
+    fn from_twos_complement(digits: Vec<u32>) -> BigInt {
+        match digits.last() {
+            Some(top) if top >> (BASE_BITS - 1) == 1 => {
+                let inverted = digits.iter().map(|digit| !digit).collect::<Vec<_>>();
+                BigInt::from_parts(true, add(&inverted, &[1]))
+            }
+            _ => BigInt::from_parts(false, digits),
+        }
+    }
+
+    fn bitwise(&self, rhs: &BigInt, op: impl Fn(u32, u32) -> u32) -> BigInt {
+        // one more digit leaves room for the sign
+        let len = self.magnitude.len().max(rhs.magnitude.len()) + 1;
+        let digits = self
+            .twos_complement(len)
+            .into_iter()
+            .zip(rhs.twos_complement(len))
+            .map(|(lhs, rhs)| op(lhs, rhs))
+            .collect();
+        BigInt::from_twos_complement(digits)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(integer: i128) -> Self {
+        let magnitude = BigInt::from_unsigned(integer.unsigned_abs()).magnitude;
+        BigInt::from_parts(integer < 0, magnitude)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare(&self.magnitude, &other.magnitude),
+            (true, true) => compare(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // decimal chunks, least significant first
+        let mut chunks = vec![];
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            let (quotient, chunk) = div_rem_digit(&rest, DECIMAL_CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        let mut text = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            text += &format!("{chunk:0width$}", width = DECIMAL_CHUNK_DIGITS);
+        }
+        f.pad_integral(!self.negative, "", &text)
+    }
+}
+
+impl Number for BigInt {
+    fn zero() -> Self {
+        BigInt::default()
+    }
+
+    /// Digits are read in chunks, each one shifting the ones before it
+    fn from_literal(literal: &str, _rounding: Rounding) -> Option<Self> {
+        if !literal.chars().all(|ch| ch.is_digit(RADIX)) {
+            return None;
+        }
+        let mut magnitude = vec![];
+        let first = literal.len() % DECIMAL_CHUNK_DIGITS;
+        let chunks = std::iter::once(&literal[..first]).chain(
+            literal.as_bytes()[first..]
+                .chunks(DECIMAL_CHUNK_DIGITS)
+                .map(|chunk| std::str::from_utf8(chunk).expect("ASCII digits")),
+        );
+        for chunk in chunks.filter(|chunk| !chunk.is_empty()) {
+            let shift = (10u32).pow(chunk.len() as u32);
+            magnitude = add(&mul(&magnitude, &[shift]), &[chunk.parse().ok()?]);
+        }
+        Some(BigInt::from_parts(false, magnitude))
+    }
+
+    fn add(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Ok(self.plus(rhs))
+    }
+
+    fn sub(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Ok(self.plus(&rhs.negated()))
+    }
+
+    fn mul(
+        &self,
+        rhs: &Self,
+        _mode: ArithmeticMode,
+        _rounding: Rounding,
+    ) -> Result<Self, ErrorKind> {
+        Ok(self.times(rhs))
+    }
+
+    fn div(
+        &self,
+        rhs: &Self,
+        _mode: ArithmeticMode,
+        _rounding: Rounding,
+    ) -> Result<Self, ErrorKind> {
+        Ok(self.div_rem(rhs)?.0)
+    }
+
+    fn rem(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Ok(self.div_rem(rhs)?.1)
+    }
+
+    fn rem_euclid(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let (_, remainder) = self.div_rem(rhs)?;
+        match remainder.negative {
+            true => Ok(remainder.plus(&rhs.abs())),
+            false => Ok(remainder),
+        }
+    }
+
+    fn div_floor(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let (quotient, remainder) = self.div_rem(rhs)?;
+        // inexact quotient of operands with different signs is rounded up
+        match !remainder.is_zero() && self.negative != rhs.negative {
+            true => Ok(quotient.plus(&BigInt::from(-1))),
+            false => Ok(quotient),
+        }
+    }
+
+    /// Negative exponent truncates the fraction like division does
+    fn pow(
+        &self,
+        rhs: &Self,
+        _mode: ArithmeticMode,
+        _rounding: Rounding,
+        max_bits: usize,
+    ) -> Result<Self, ErrorKind> {
+        let unit = self.magnitude == [1];
+        if rhs.negative || unit || self.is_zero() {
+            return match () {
+                _ if self.is_zero() && rhs.negative => Err(ErrorKind::DivisionByZero),
+                _ if unit && self.negative && rhs.is_odd() => Ok(self.clone()),
+                _ if unit || rhs.is_zero() => Ok(BigInt::from(1)),
+                _ => Ok(BigInt::zero()),
+            };
+        }
+        // result has more than `exponent` times the bits of the base less one,
+        // so computing it takes at most twice the bits allowed
+        let too_large = ErrorKind::ResultTooLarge { max_bits };
+        let mut exponent = rhs.to_usize().ok_or(too_large)?;
+        if exponent.saturating_mul(self.bits() - 1) >= max_bits {
+            return Err(too_large);
+        }
+        let (mut base, mut result) = (self.clone(), BigInt::from(1));
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = result.times(&base);
+            }
+            exponent /= 2;
+            if exponent > 0 {
+                base = base.times(&base);
+            }
+        }
+        if result.bits() > max_bits {
+            return Err(too_large);
+        }
+        Ok(result)
+    }
+
+    fn neg(&self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Ok(self.negated())
+    }
+
+    fn bit_and(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+        Ok(self.bitwise(rhs, |lhs, rhs| lhs & rhs))
+    }
+
+    fn bit_or(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+        Ok(self.bitwise(rhs, |lhs, rhs| lhs | rhs))
+    }
+
+    fn bit_xor(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+        Ok(self.bitwise(rhs, |lhs, rhs| lhs ^ rhs))
+    }
+
+    /// `!x` is `-x - 1`
+    fn bit_not(&self) -> Result<Self, ErrorKind> {
+        Ok(self.negated().plus(&BigInt::from(-1)))
+    }
+
+    fn shl(&self, rhs: &Self, _mode: ArithmeticMode, max_bits: usize) -> Result<Self, ErrorKind> {
+        if rhs.negative {
+            return Err(ErrorKind::NegativeShift);
+        }
+        if self.is_zero() {
+            return Ok(BigInt::zero());
+        }
+        let too_large = ErrorKind::ResultTooLarge { max_bits };
+        let bits = rhs.to_usize().ok_or(too_large)?;
+        if bits.saturating_add(self.bits()) > max_bits {
+            return Err(too_large);
+        }
+        Ok(BigInt::from_parts(
+            self.negative,
+            shift_left(&self.magnitude, bits),
+        ))
+    }
+
+    /// Rounds down like division by a power of 2 would
+    fn shr(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+        if rhs.negative {
+            return Err(ErrorKind::NegativeShift);
+        }
+        let bits = rhs.to_usize().unwrap_or(usize::MAX);
+        match self.negative {
+            // -x >> n is !(!(-x) >> n), and !(-x) is x - 1
+            true => {
+                let inverted = sub(&self.magnitude, &[1]);
+                let shifted = BigInt::from_parts(false, shift_right(&inverted, bits));
+                Ok(shifted.negated().plus(&BigInt::from(-1)))
+            }
+            false => Ok(BigInt::from_parts(
+                false,
+                shift_right(&self.magnitude, bits),
+            )),
+        }
+    }
+
+    fn to_radix(&self, radix: u32) -> Option<(bool, String)> {
+        let mut digits = vec![];
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            let (quotient, digit) = div_rem_digit(&rest, radix);
+            digits.push(char::from_digit(digit, radix).expect("digit below radix"));
+            rest = quotient;
+        }
+        if digits.is_empty() {
+            digits.push('0');
+        }
+        let digits = digits.iter().rev().collect::<String>().to_uppercase();
+        Some((self.negative, digits))
+    }
+
+    fn to_fraction(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+/// Drop leading zero digits
+fn trim(digits: &mut Vec<u32>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+/// Compare magnitudes without leading zeros
+fn compare(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    lhs.len()
+        .cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+fn add(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let (long, short) = if lhs.len() >= rhs.len() {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (index, &digit) in long.iter().enumerate() {
+        let total = digit as u64 + *short.get(index).unwrap_or(&0) as u64 + carry;
+        sum.push(total as u32);
+        carry = total >> BASE_BITS;
+    }
+    sum.push(carry as u32);
+    trim(&mut sum);
+    sum
+}
+
+/// `lhs - rhs`, where `lhs` is not smaller than `rhs`
+fn sub(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(lhs.len());
+    let mut borrow = 0i64;
+    for (index, &digit) in lhs.iter().enumerate() {
+        let total = digit as i64 - *rhs.get(index).unwrap_or(&0) as i64 - borrow;
+        difference.push(total as u32);
+        borrow = i64::from(total < 0);
+    }
+    trim(&mut difference);
+    difference
+}
+
+/// Schoolbook multiplication
+fn mul(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; lhs.len() + rhs.len()];
+    for (i, &lhs_digit) in lhs.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &rhs_digit) in rhs.iter().enumerate() {
+            let total = lhs_digit as u64 * rhs_digit as u64 + product[i + j] as u64 + carry;
+            product[i + j] = total as u32;
+            carry = total >> BASE_BITS;
+        }
+        product[i + rhs.len()] = carry as u32;
+    }
+    trim(&mut product);
+    product
+}
+
+/// Quotient and remainder of division by a single nonzero digit
+fn div_rem_digit(lhs: &[u32], rhs: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; lhs.len()];
+    let mut remainder = 0u64;
+    for (index, &digit) in lhs.iter().enumerate().rev() {
+        let current = (remainder << BASE_BITS) | digit as u64;
+        quotient[index] = (current / rhs as u64) as u32;
+        remainder = current % rhs as u64;
+    }
+    trim(&mut quotient);
+    (quotient, remainder as u32)
+}
+
+/// Quotient and remainder of magnitudes, `rhs` is not zero.
+/// Long division of Knuth's Algorithm D
+fn div_rem(lhs: &[u32], rhs: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare(lhs, rhs) == Ordering::Less {
+        return (vec![], lhs.to_vec());
+    }
+    if let [digit] = rhs {
+        let (quotient, remainder) = div_rem_digit(lhs, *digit);
+        return (
+            quotient,
+            [remainder].into_iter().filter(|&r| r != 0).collect(),
+        );
+    }
+
+    // normalize so the top digit of the divisor has its high bit set,
+    // which keeps every estimated quotient digit off by at most 2
+    let shift = rhs.last().expect("nonzero divisor").leading_zeros() as usize;
+    let divisor = shift_left(rhs, shift);
+    let mut rest = shift_left(lhs, shift);
+    rest.resize(lhs.len() + 1, 0);
+
+    let n = divisor.len();
+    let (top, second) = (divisor[n - 1] as u64, divisor[n - 2] as u64);
+    let mut quotient = vec![0u32; rest.len() - n];
+    for j in (0..quotient.len()).rev() {
+        let current = ((rest[j + n] as u64) << BASE_BITS) | rest[j + n - 1] as u64;
+        let (mut estimate, mut remainder) = (current / top, current % top);
+        while estimate >> BASE_BITS != 0
+            || estimate * second > ((remainder << BASE_BITS) | rest[j + n - 2] as u64)
+        {
+            estimate -= 1;
+            remainder += top;
+            if remainder >> BASE_BITS != 0 {
+                break;
+            }
+        }
+
+        // subtract estimate times the divisor
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let product = estimate * divisor[i] as u64;
+            let total = rest[i + j] as i64 - borrow - (product & 0xFFFF_FFFF) as i64;
+            rest[i + j] = total as u32;
+            borrow = (product >> BASE_BITS) as i64 - (total >> BASE_BITS);
+        }
+        let total = rest[j + n] as i64 - borrow;
+        rest[j + n] = total as u32;
+
+        // estimate was one too large, add the divisor back
+        if total < 0 {
+            estimate -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let total = rest[i + j] as u64 + divisor[i] as u64 + carry;
+                rest[i + j] = total as u32;
+                carry = total >> BASE_BITS;
+            }
+            rest[j + n] = rest[j + n].wrapping_add(carry as u32);
+        }
+        quotient[j] = estimate as u32;
+    }
+
+    trim(&mut quotient);
+    rest.truncate(n);
+    (quotient, shift_right(&rest, shift))
+}
+
+fn shift_left(digits: &[u32], bits: usize) -> Vec<u32> {
+    let (whole, part) = (bits / BASE_BITS, bits % BASE_BITS);
+    let mut shifted = vec![0u32; whole];
+    let mut carry = 0u32;
+    for &digit in digits {
+        shifted.push((digit << part) | carry);
+        carry = match part {
+            0 => 0,
+            _ => digit >> (BASE_BITS - part),
+        };
+    }
+    shifted.push(carry);
+    trim(&mut shifted);
+    shifted
+}
+
+fn shift_right(digits: &[u32], bits: usize) -> Vec<u32> {
+    let (whole, part) = (bits / BASE_BITS, bits % BASE_BITS);
+    let Some(kept) = digits.get(whole..) else {
+        return vec![];
+    };
+    let mut shifted = kept
+        .iter()
+        .enumerate()
+        .map(|(index, &digit)| {
+            let high = match part {
+                0 => 0,
+                _ => kept
+                    .get(index + 1)
+                    .map_or(0, |next| next << (BASE_BITS - part)),
+            };
+            (digit >> part) | high
+        })
+        .collect();
+    trim(&mut shifted);
+    shifted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(literal: &str) -> BigInt {
+        match literal.strip_prefix('-') {
+            Some(digits) => BigInt::from_literal(digits, Rounding::default())
+                .unwrap()
+                .negated(),
+            None => BigInt::from_literal(literal, Rounding::default()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn literals_and_display() {
+        assert_eq!(big("0"), BigInt::zero());
+        assert_eq!(big("000123"), BigInt::from(123));
+        assert_eq!(
+            big("-170141183460469231731687303715884105728"),
+            BigInt::from(i128::MIN)
+        );
+        let digits = "1606938044258990275541962092341162602522202993782792835301376";
+        assert_eq!(big(digits).to_string(), digits);
+        assert_eq!(BigInt::from(-1_000_000_000).to_string(), "-1000000000");
+        assert_eq!(format!("{:>6}", BigInt::from(-42)), "   -42");
+        assert_eq!(BigInt::from_literal("12a", Rounding::default()), None);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        let lhs = big("123456789012345678901234567890123456789012345678901234567890");
+        let rhs = big("987654321098765432109876543210987654321098765432109876543210");
+        assert_eq!(
+            lhs.mul(&rhs, mode, rounding).map(|product| product.to_string()),
+            Ok("121932631137021795226185032733866788594511507391563633592367367779295611949397448712086533622923332237463801111263526900".to_string())
+        );
+        assert_eq!(
+            lhs.sub(&rhs, mode)
+                .and_then(|difference| difference.add(&rhs, mode)),
+            Ok(lhs.clone())
+        );
+        assert_eq!(
+            lhs.div(&big("-97"), mode, rounding),
+            Ok(big(
+                "-1272750402189130710322005854537355224628993254421662212040"
+            ))
+        );
+        assert_eq!(lhs.rem(&big("-97"), mode), Ok(BigInt::from(10)));
+        assert_eq!(
+            lhs.div_floor(&big("-97"), mode),
+            Ok(big(
+                "-1272750402189130710322005854537355224628993254421662212041"
+            ))
+        );
+        let ten_to_40 = BigInt::from(10)
+            .pow(&BigInt::from(40), mode, rounding, MAX_BITS)
+            .unwrap();
+        assert_eq!(
+            ten_to_40.negated().div_floor(&BigInt::from(7), mode),
+            Ok(big("-1428571428571428571428571428571428571429"))
+        );
+        assert_eq!(
+            ten_to_40.negated().rem_euclid(&BigInt::from(7), mode),
+            Ok(BigInt::from(3))
+        );
+        assert_eq!(
+            rhs.div(&BigInt::zero(), mode, rounding),
+            Err(ErrorKind::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn long_division() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        let lhs = big("121932631137021795226185032733866788594511507391563633592367367779295611949397448712086533622923332237463801111263526900");
+        let rhs = big("987654321098765432109876543210987654321098765432109876543210");
+        assert_eq!(
+            lhs.div(&rhs, mode, rounding),
+            Ok(big(
+                "123456789012345678901234567890123456789012345678901234567890"
+            ))
+        );
+        assert_eq!(lhs.rem(&rhs, mode), Ok(BigInt::zero()));
+
+        // every quotient and remainder is consistent with the dividend
+        let divisors = [
+            "4294967295",
+            "4294967296",
+            "18446744073709551617",
+            "340282366920938463463374607431768211455",
+        ];
+        for divisor in divisors {
+            let divisor = big(divisor);
+            let (quotient, remainder) = lhs.div_rem(&divisor).unwrap();
+            assert!(remainder < divisor);
+            assert_eq!(quotient.times(&divisor).plus(&remainder), lhs);
+        }
+    }
+
+    #[test]
+    fn powers() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        assert_eq!(
+            BigInt::from(2)
+                .pow(&BigInt::from(200), mode, rounding, MAX_BITS)
+                .map(|power| power.to_string()),
+            Ok("1606938044258990275541962092341162602522202993782792835301376".to_string())
+        );
+        assert_eq!(
+            BigInt::from(-1).pow(&big("1000000000000000000001"), mode, rounding, MAX_BITS),
+            Ok(BigInt::from(-1))
+        );
+        assert_eq!(
+            BigInt::from(2).pow(&BigInt::from(-3), mode, rounding, MAX_BITS),
+            Ok(BigInt::zero())
+        );
+        assert_eq!(
+            BigInt::zero().pow(&BigInt::from(-3), mode, rounding, MAX_BITS),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(
+            BigInt::from(2).pow(&big("100000000000000000000000"), mode, rounding, MAX_BITS),
+            Err(ErrorKind::ResultTooLarge { max_bits: MAX_BITS })
+        );
+        // results too long to compute in reasonable time
+        assert_eq!(
+            BigInt::from(3).pow(&BigInt::from(3_000_000), mode, rounding, MAX_BITS),
+            Err(ErrorKind::ResultTooLarge { max_bits: MAX_BITS })
+        );
+        assert_eq!(
+            BigInt::from(2)
+                .pow(
+                    &BigInt::from(MAX_BITS as i128 / 2),
+                    mode,
+                    rounding,
+                    MAX_BITS
+                )
+                .map(|power| power.bits()),
+            Ok(MAX_BITS / 2 + 1)
+        );
+        // 3^40 has 64 bits and 3^41 has 65
+        assert_eq!(
+            BigInt::from(3).pow(&BigInt::from(40), mode, rounding, 64),
+            Ok(BigInt::from(3i128.pow(40)))
+        );
+        assert_eq!(
+            BigInt::from(3).pow(&BigInt::from(41), mode, rounding, 64),
+            Err(ErrorKind::ResultTooLarge { max_bits: 64 })
+        );
+        assert_eq!(
+            BigInt::from(-2).pow(&BigInt::from(64), mode, rounding, 64),
+            Err(ErrorKind::ResultTooLarge { max_bits: 64 })
+        );
+    }
+
+    #[test]
+    fn bitwise() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        for (lhs, rhs) in [
+            (12i128, 10i128),
+            (-12, 10),
+            (12, -10),
+            (-12, -10),
+            (1 << 70, -1),
+        ] {
+            let (big_lhs, big_rhs) = (BigInt::from(lhs), BigInt::from(rhs));
+            assert_eq!(big_lhs.bit_and(&big_rhs), Ok(BigInt::from(lhs & rhs)));
+            assert_eq!(big_lhs.bit_or(&big_rhs), Ok(BigInt::from(lhs | rhs)));
+            assert_eq!(big_lhs.bit_xor(&big_rhs), Ok(BigInt::from(lhs ^ rhs)));
+            assert_eq!(big_lhs.bit_not(), Ok(BigInt::from(!lhs)));
+        }
+        assert_eq!(
+            BigInt::from(-3).shl(&BigInt::from(100), mode, MAX_BITS),
+            Ok(BigInt::from(-3).times(
+                &BigInt::from(2)
+                    .pow(&BigInt::from(100), mode, rounding, MAX_BITS)
+                    .unwrap()
+            ))
+        );
+        assert_eq!(BigInt::from(-7).shr(&BigInt::from(1)), Ok(BigInt::from(-4)));
+        assert_eq!(
+            BigInt::from(-1 << 80).shr(&BigInt::from(80)),
+            Ok(BigInt::from(-1))
+        );
+        assert_eq!(
+            BigInt::from(-5).shr(&big("100000000000000000000000")),
+            Ok(BigInt::from(-1))
+        );
+        assert_eq!(BigInt::from(5).shr(&BigInt::from(64)), Ok(BigInt::zero()));
+        assert_eq!(
+            BigInt::from(5).shl(&BigInt::from(-1), mode, MAX_BITS),
+            Err(ErrorKind::NegativeShift)
+        );
+        assert_eq!(
+            BigInt::from(1).shl(&big("100000000000"), mode, MAX_BITS),
+            Err(ErrorKind::ResultTooLarge { max_bits: MAX_BITS })
+        );
+        assert_eq!(
+            BigInt::from(1)
+                .shl(&BigInt::from(MAX_BITS as i128 - 1), mode, MAX_BITS)
+                .map(|shifted| shifted.bits()),
+            Ok(MAX_BITS)
+        );
+        assert_eq!(
+            BigInt::from(2).shl(&BigInt::from(MAX_BITS as i128 - 1), mode, MAX_BITS),
+            Err(ErrorKind::ResultTooLarge { max_bits: MAX_BITS })
+        );
+    }
+}
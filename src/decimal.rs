@@ -0,0 +1,616 @@
+//! Fixed-point numbers with a set count of fraction digits, like money
+//!
+//! Example:
+//! ```
+//! use parser_rs::{Decimal, ErrorKind, EvalOptions, Evaluator, Rounding};
+//!
+//! let evaluator = Evaluator::<Decimal<2>>::with_backend(EvalOptions::new());
+//! assert_eq!(evaluator.evaluate("10d3").unwrap().to_string(), "3.33");
+//! assert_eq!(evaluator.evaluate("1.5 c 1.5").unwrap().to_string(), "2.25");
+//! // literals are rounded too
+//! assert_eq!(evaluator.evaluate("0.125").unwrap().to_string(), "0.12");
+//! assert_eq!(evaluator.evaluate("1e37").map_err(|e| e.kind), Err(ErrorKind::LiteralOverflow));
+//!
+//! let half_up = Evaluator::<Decimal<2>>::with_backend(EvalOptions::new().rounding(Rounding::HalfUp));
+//! assert_eq!(half_up.evaluate("0.25 d 2").unwrap().to_string(), "0.13");
+//! let half_even = Evaluator::<Decimal<2>>::with_backend(EvalOptions::new());
+//! assert_eq!(half_even.evaluate("0.25 d 2").unwrap().to_string(), "0.12");
+//! ```
+
+use crate::number::radix_digits;
+use crate::{ArithmeticMode, BigInt, ErrorKind, Number, Rational, Rounding};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Decimal number with exactly `SCALE` digits after the point, kept as an `i128`
+/// count of the smallest step, `10^-SCALE`
+///
+/// Addition and subtraction are exact unless they overflow.
+/// Products, quotients and powers with more fraction digits
+/// are rounded once by the [`Rounding`] of the evaluator,
+/// and so are literals with more fraction digits than `SCALE`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Decimal<const SCALE: u32 = 2> {
+    units: i128,
+}
+
+impl<const SCALE: u32> Decimal<SCALE> {
+    /// Units in 1. `SCALE` above 38 fails to compile
+    const ONE: i128 = 10i128.pow(SCALE);
+
+    /// Number that is `units` times `10^-SCALE`.
+    /// `Decimal::<2>::from_units(150)` is `1.50`
+    pub fn from_units(units: i128) -> Self {
+        Decimal { units }
+    }
+
+    /// Count of `10^-SCALE` in the number
+    pub fn units(self) -> i128 {
+        self.units
+    }
+
+    /// `(numerator / denominator)^power`, negated if `negative`,
+    /// for powers too large to compute exactly
+    ///
+    /// Lower and upper bounds of the power are computed in fixed point,
+    /// with more fraction digits until both round to the same units.
+    /// Powers with a bound surely out of range are an overflow,
+    /// or saturate, and those surely below half a unit round from 0
+    fn bounded_pow(
+        numerator: u128,
+        denominator: u128,
+        power: u128,
+        negative: bool,
+        mode: ArithmeticMode,
+        rounding: Rounding,
+    ) -> Result<Self, ErrorKind> {
+        let growing = numerator > denominator;
+        let mut digits = SCALE + 2 * OUT_OF_RANGE_DIGITS;
+        while digits <= MAX_POWER_DIGITS {
+            let one = ten_to(digits);
+            let huge = ten_to(digits + OUT_OF_RANGE_DIGITS);
+            let tiny = ten_to(digits - SCALE - 1);
+            let times = |(lhs_lower, lhs_upper): &Bounds, (rhs_lower, rhs_upper): &Bounds| {
+                divide_bounds(lhs_lower.times(rhs_lower), lhs_upper.times(rhs_upper), &one)
+            };
+            // every square and partial product lies between 1 and the whole power
+            let beyond = |(lower, upper): &Bounds| match growing {
+                true => *lower > huge,
+                false => *upper < tiny,
+            };
+
+            let scaled = BigInt::from_unsigned(numerator).times(&one);
+            let mut square =
+                divide_bounds(scaled.clone(), scaled, &BigInt::from_unsigned(denominator))?;
+            let mut result = (one.clone(), one.clone());
+            let mut rest = power;
+            while rest > 0 && !beyond(&result) && !beyond(&square) {
+                if rest % 2 == 1 {
+                    result = times(&result, &square)?;
+                }
+                rest /= 2;
+                if rest > 0 {
+                    square = times(&square, &square)?;
+                }
+            }
+            match (beyond(&result) || beyond(&square), growing) {
+                (true, true) => {
+                    return match mode {
+                        ArithmeticMode::Saturating if negative => {
+                            Ok(Decimal::from_units(i128::MIN))
+                        }
+                        ArithmeticMode::Saturating => Ok(Decimal::from_units(i128::MAX)),
+                        ArithmeticMode::Checked | ArithmeticMode::Wrapping => {
+                            Err(ErrorKind::Overflow)
+                        }
+                    };
+                }
+                (true, false) => {
+                    let units = round(BigInt::zero(), negative, Ordering::Less, rounding);
+                    return Self::fit(units, mode);
+                }
+                (false, _) => {}
+            }
+
+            let sign = BigInt::from(if negative { -1 } else { 1 });
+            let unit = ten_to(digits - SCALE);
+            let (lower, upper) = result;
+            let lower = divide(lower.times(&sign), unit.clone(), rounding)?;
+            let upper = divide(upper.times(&sign), unit, rounding)?;
+            if lower == upper {
+                return Self::fit(lower, mode);
+            }
+            digits *= 2;
+        }
+        Err(ErrorKind::Overflow)
+    }
+
+    /// Number as units, or what is left of it in `mode` when it does not fit
+    fn fit(units: BigInt, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let units = match (units.to_i128(), mode) {
+            (Some(units), _) => units,
+            (None, ArithmeticMode::Checked) => return Err(ErrorKind::Overflow),
+            (None, ArithmeticMode::Wrapping) => units.wrapping_i128(),
+            (None, ArithmeticMode::Saturating) if units.is_negative() => i128::MIN,
+            (None, ArithmeticMode::Saturating) => i128::MAX,
+        };
+        Ok(Decimal::from_units(units))
+    }
+}
+
+/// `numerator / denominator` rounded to an integer
+fn divide(numerator: BigInt, denominator: BigInt, rounding: Rounding) -> Result<BigInt, ErrorKind> {
+    let (quotient, remainder) = numerator.div_rem(&denominator)?;
+    if remainder.is_zero() {
+        return Ok(quotient);
+    }
+    let negative = remainder.is_negative() != denominator.is_negative();
+    let half = remainder
+        .abs()
+        .times(&BigInt::from(2))
+        .cmp(&denominator.abs());
+    Ok(round(quotient, negative, half, rounding))
+}
+
+/// Integer part `quotient` of an inexact result, stepped one away from zero when needed.
+/// `half` compares the dropped fraction with one half
+fn round(quotient: BigInt, negative: bool, half: Ordering, rounding: Rounding) -> BigInt {
+    let away = match rounding {
+        Rounding::HalfEven => {
+            half == Ordering::Greater || (half == Ordering::Equal && quotient.is_odd())
+        }
+        Rounding::HalfUp => half != Ordering::Less,
+        Rounding::TowardZero => false,
+        Rounding::Floor => negative,
+        Rounding::Ceiling => !negative,
+    };
+    match (away, negative) {
+        (false, _) => quotient,
+        (true, false) => quotient.plus(&BigInt::from(1)),
+        (true, true) => quotient.plus(&BigInt::from(-1)),
+    }
+}
+
+/// Largest power computed exactly, in bits of its numerator or denominator
+const MAX_POWER_BITS: u32 = 1 << 16;
+
+/// Most fraction digits bounds of a larger power are computed with
+const MAX_POWER_DIGITS: u32 = 1 << 12;
+
+/// Integer digits of powers surely out of range of any `Decimal`
+const OUT_OF_RANGE_DIGITS: u32 = 40;
+
+/// `10^exponent`
+fn ten_to(exponent: u32) -> BigInt {
+    let ten = BigInt::from(10);
+    let exponent = BigInt::from(i128::from(exponent));
+    ten.pow(
+        &exponent,
+        ArithmeticMode::Checked,
+        Rounding::default(),
+        usize::MAX,
+    )
+    .expect("small power of ten")
+}
+
+/// Lower and upper bound of a positive number, in units of a fixed point
+type Bounds = (BigInt, BigInt);
+
+/// Bounds of `lower / divisor` and `upper / divisor`, widened to whole units
+fn divide_bounds(lower: BigInt, upper: BigInt, divisor: &BigInt) -> Result<Bounds, ErrorKind> {
+    let (lower, _) = lower.div_rem(divisor)?;
+    let (upper, remainder) = upper.div_rem(divisor)?;
+    match remainder.is_zero() {
+        true => Ok((lower, upper)),
+        false => Ok((lower, upper.plus(&BigInt::from(1)))),
+    }
+}
+
+impl<const SCALE: u32> fmt::Display for Decimal<SCALE> {
+    /// All `SCALE` digits after the point unless a precision is given.
+    /// Fewer digits round half away from zero, like `Rational` does
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = SCALE as usize;
+        let precision = f.precision().unwrap_or(scale);
+        let mut magnitude = self.units.unsigned_abs();
+        if precision < scale {
+            let step = 10u128.pow(SCALE - precision as u32);
+            let rest = magnitude % step;
+            magnitude -= rest;
+            if rest >= step - rest {
+                magnitude += step;
+            }
+        }
+
+        let one = Self::ONE.unsigned_abs();
+        let (integer, fraction) = (magnitude / one, magnitude % one);
+        let mut text = integer.to_string();
+        if precision > 0 {
+            let fraction = format!("{fraction:0scale$}");
+            let kept = &fraction[..precision.min(scale)];
+            text = format!("{text}.{kept:0<precision$}");
+        }
+        // no sign on a number rounded to zero
+        f.pad_integral(self.units >= 0 || magnitude == 0, "", &text)
+    }
+}
+
+impl<const SCALE: u32> Number for Decimal<SCALE> {
+    const FRACTIONAL: bool = true;
+
+    fn zero() -> Self {
+        Decimal::default()
+    }
+
+    /// Digits past `SCALE` are rounded as written, before any sign in front of the literal,
+    /// so `1.005` is `1.00` and `1.500e1` is `15.00`
+    fn from_literal(literal: &str, rounding: Rounding) -> Option<Self> {
+        let (mantissa, exponent) = match literal.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+            None => (literal, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = format!("{integer}{fraction}");
+        let digits = digits.trim_start_matches('0');
+        // how far the last digit is from the smallest unit
+        let shift = exponent
+            .checked_sub(i64::try_from(fraction.len()).ok()?)?
+            .checked_add(SCALE as i64)?;
+        let units = match usize::try_from(shift.checked_neg()?) {
+            Ok(dropped) => {
+                let kept = digits.len().saturating_sub(dropped);
+                let units = match kept {
+                    0 => 0,
+                    _ => digits[..kept].parse().ok()?,
+                };
+                let rest = digits[kept..].trim_end_matches('0');
+                if rest.is_empty() {
+                    units
+                } else {
+                    // dropped digits against one half, `5` right after the kept ones
+                    let half = match digits.len() - kept < dropped {
+                        true => Ordering::Less,
+                        false => rest.cmp("5"),
+                    };
+                    round(BigInt::from(units), false, half, rounding).to_i128()?
+                }
+            }
+            Err(_) if digits.is_empty() => 0,
+            Err(_) => {
+                let power = 10i128.checked_pow(u32::try_from(shift).ok()?)?;
+                digits.parse::<i128>().ok()?.checked_mul(power)?
+            }
+        };
+        Some(Decimal::from_units(units))
+    }
+
+    fn add(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let units = mode.add(self.units, rhs.units).ok_or(ErrorKind::Overflow)?;
+        Ok(Decimal::from_units(units))
+    }
+
+    fn sub(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let units = mode.sub(self.units, rhs.units).ok_or(ErrorKind::Overflow)?;
+        Ok(Decimal::from_units(units))
+    }
+
+    fn mul(&self, rhs: &Self, mode: ArithmeticMode, rounding: Rounding) -> Result<Self, ErrorKind> {
+        let product = BigInt::from(self.units).times(&BigInt::from(rhs.units));
+        Self::fit(divide(product, BigInt::from(Self::ONE), rounding)?, mode)
+    }
+
+    fn div(&self, rhs: &Self, mode: ArithmeticMode, rounding: Rounding) -> Result<Self, ErrorKind> {
+        let numerator = BigInt::from(self.units).times(&BigInt::from(Self::ONE));
+        Self::fit(divide(numerator, BigInt::from(rhs.units), rounding)?, mode)
+    }
+
+    /// Exact, remainder of the division truncated to an integer
+    fn rem(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        nonzero(rhs)?;
+        Ok(Decimal::from_units(self.units.wrapping_rem(rhs.units)))
+    }
+
+    fn rem_euclid(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        nonzero(rhs)?;
+        Ok(Decimal::from_units(
+            self.units.wrapping_rem_euclid(rhs.units),
+        ))
+    }
+
+    fn div_floor(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        nonzero(rhs)?;
+        let quotient = mode
+            .div_floor(self.units, rhs.units)
+            .ok_or(ErrorKind::Overflow)?;
+        Self::fit(BigInt::from(quotient).times(&BigInt::from(Self::ONE)), mode)
+    }
+
+    /// Only integer exponents are supported.
+    /// Powers too large to compute exactly are rounded from bounds close enough around them
+    fn pow(
+        &self,
+        rhs: &Self,
+        mode: ArithmeticMode,
+        rounding: Rounding,
+        _max_bits: usize,
+    ) -> Result<Self, ErrorKind> {
+        if rhs.units % Self::ONE != 0 {
+            return Err(ErrorKind::UnsupportedOperation);
+        }
+        let exponent = rhs.units / Self::ONE;
+        let negative = self.units < 0 && exponent % 2 != 0;
+        let one = Decimal::from_units(Self::ONE);
+        match self.units.unsigned_abs() {
+            0 if exponent < 0 => return Err(ErrorKind::DivisionByZero),
+            _ if exponent == 0 => return Ok(one),
+            0 => return Ok(Self::zero()),
+            units if units == Self::ONE.unsigned_abs() => {
+                return match negative {
+                    true => one.neg(mode),
+                    false => Ok(one),
+                };
+            }
+            _ => {}
+        }
+
+        // |self|^exponent as numerator^power / denominator^power
+        let (numerator, denominator) = match exponent > 0 {
+            true => (self.units.unsigned_abs(), Self::ONE.unsigned_abs()),
+            false => (Self::ONE.unsigned_abs(), self.units.unsigned_abs()),
+        };
+        let power = exponent.unsigned_abs();
+        let bits = 128 - numerator.max(denominator).leading_zeros();
+        if power.saturating_mul(bits as u128) > MAX_POWER_BITS as u128 {
+            return Self::bounded_pow(numerator, denominator, power, negative, mode, rounding);
+        }
+
+        let power = BigInt::from(power as i128);
+        let (checked, max_bits) = (ArithmeticMode::Checked, MAX_POWER_BITS as usize);
+        let numerator =
+            BigInt::from_unsigned(numerator).pow(&power, checked, rounding, max_bits)?;
+        let denominator =
+            BigInt::from_unsigned(denominator).pow(&power, checked, rounding, max_bits)?;
+        let sign = BigInt::from(if negative { -1 } else { 1 });
+        let numerator = numerator.times(&BigInt::from(Self::ONE)).times(&sign);
+        Self::fit(divide(numerator, denominator, rounding)?, mode)
+    }
+
+    fn to_radix(&self, radix: u32) -> Option<(bool, String)> {
+        match self.units % Self::ONE {
+            0 => Some((
+                self.units < 0,
+                radix_digits((self.units / Self::ONE).unsigned_abs(), radix),
+            )),
+            _ => None,
+        }
+    }
+
+    /// In lowest terms, `0.50` is `1/2`
+    fn to_fraction(&self) -> Option<String> {
+        Rational::new(self.units, Self::ONE).map(|fraction| fraction.to_string())
+    }
+}
+
+fn nonzero<const SCALE: u32>(divisor: &Decimal<SCALE>) -> Result<(), ErrorKind> {
+    match divisor.units == 0 {
+        true => Err(ErrorKind::DivisionByZero),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_int::MAX_BITS;
+
+    fn cents(units: i128) -> Decimal {
+        Decimal::from_units(units)
+    }
+
+    #[test]
+    fn literals() {
+        let literal = |literal| Decimal::<2>::from_literal(literal, Rounding::default());
+        assert_eq!(literal("1.5"), Some(cents(150)));
+        assert_eq!(literal("0.120"), Some(cents(12)));
+        assert_eq!(literal("1.500e1"), Some(cents(1500)));
+        assert_eq!(literal("12e-1"), Some(cents(120)));
+        assert_eq!(literal("0e-999"), Some(cents(0)));
+        assert_eq!(literal("1e37"), None);
+        assert_eq!(
+            Decimal::<0>::from_literal("42", Rounding::default()),
+            Some(Decimal::from_units(42))
+        );
+
+        // extra fraction digits are rounded
+        assert_eq!(literal("0.125"), Some(cents(12)));
+        assert_eq!(literal("0.135"), Some(cents(14)));
+        assert_eq!(literal("1.0051"), Some(cents(101)));
+        assert_eq!(literal("0.994999"), Some(cents(99)));
+        assert_eq!(literal("1e-3"), Some(cents(0)));
+        let rounded = |literal, rounding| Decimal::<2>::from_literal(literal, rounding);
+        assert_eq!(rounded("0.125", Rounding::HalfUp), Some(cents(13)));
+        assert_eq!(rounded("0.121", Rounding::Ceiling), Some(cents(13)));
+        assert_eq!(rounded("1e-999", Rounding::Ceiling), Some(cents(1)));
+        assert_eq!(rounded("0.129", Rounding::Floor), Some(cents(12)));
+        assert_eq!(rounded("0.129", Rounding::TowardZero), Some(cents(12)));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(cents(-50).to_string(), "-0.50");
+        assert_eq!(cents(123456).to_string(), "1234.56");
+        assert_eq!(format!("{:>8}", cents(7)), "    0.07");
+        assert_eq!(Decimal::<0>::from_units(-7).to_string(), "-7");
+        assert_eq!(Decimal::<4>::from_units(1).to_string(), "0.0001");
+        assert_eq!(format!("{:.1}", cents(-125)), "-1.3");
+        assert_eq!(format!("{:.0}", cents(-50)), "-1");
+        assert_eq!(format!("{:.1}", cents(-4)), "0.0");
+        assert_eq!(format!("{:.4}", cents(7)), "0.0700");
+        assert_eq!(format!("{:.1}", cents(9_99)), "10.0");
+        assert_eq!(format!("{:.2}", Decimal::<0>::from_units(-7)), "-7.00");
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let mode = ArithmeticMode::Checked;
+        let cases = [
+            (Rounding::HalfEven, [12, -12, 38]),
+            (Rounding::HalfUp, [13, -13, 38]),
+            (Rounding::TowardZero, [12, -12, 37]),
+            (Rounding::Floor, [12, -13, 37]),
+            (Rounding::Ceiling, [13, -12, 38]),
+        ];
+        for (rounding, [eighth, minus_eighth, three_eighths]) in cases {
+            let eight = cents(800);
+            assert_eq!(cents(100).div(&eight, mode, rounding), Ok(cents(eighth)));
+            assert_eq!(
+                cents(-100).div(&eight, mode, rounding),
+                Ok(cents(minus_eighth))
+            );
+            assert_eq!(
+                cents(300).div(&eight, mode, rounding),
+                Ok(cents(three_eighths))
+            );
+            // 0.125, 0.375 again as products
+            assert_eq!(cents(25).mul(&cents(50), mode, rounding), Ok(cents(eighth)));
+            assert_eq!(
+                cents(75).mul(&cents(50), mode, rounding),
+                Ok(cents(three_eighths))
+            );
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        assert_eq!(cents(10).add(&cents(20), mode), Ok(cents(30)));
+        assert_eq!(cents(10).sub(&cents(20), mode), Ok(cents(-10)));
+        assert_eq!(cents(150).mul(&cents(150), mode, rounding), Ok(cents(225)));
+        assert_eq!(
+            cents(100).div(&cents(0), mode, rounding),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(cents(750).div_floor(&cents(200), mode), Ok(cents(300)));
+        assert_eq!(cents(-750).div_floor(&cents(200), mode), Ok(cents(-400)));
+        assert_eq!(cents(-750).rem(&cents(200), mode), Ok(cents(-150)));
+        assert_eq!(cents(-750).rem_euclid(&cents(200), mode), Ok(cents(50)));
+        assert_eq!(
+            cents(1).rem(&cents(0), mode),
+            Err(ErrorKind::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let max = cents(i128::MAX);
+        let two = cents(200);
+        let rounding = Rounding::default();
+        assert_eq!(
+            max.mul(&two, ArithmeticMode::Checked, rounding),
+            Err(ErrorKind::Overflow)
+        );
+        assert_eq!(
+            max.mul(&two, ArithmeticMode::Wrapping, rounding),
+            Ok(cents(-2))
+        );
+        assert_eq!(
+            max.neg(ArithmeticMode::Checked).unwrap().mul(
+                &two,
+                ArithmeticMode::Saturating,
+                rounding
+            ),
+            Ok(cents(i128::MIN))
+        );
+        assert_eq!(max.add(&cents(1), ArithmeticMode::Saturating), Ok(max));
+    }
+
+    #[test]
+    fn powers() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        assert_eq!(
+            cents(110).pow(&cents(200), mode, rounding, MAX_BITS),
+            Ok(cents(121))
+        );
+        assert_eq!(
+            cents(200).pow(&cents(-100), mode, rounding, MAX_BITS),
+            Ok(cents(50))
+        );
+        assert_eq!(
+            cents(300).pow(&cents(-100), mode, rounding, MAX_BITS),
+            Ok(cents(33))
+        );
+        assert_eq!(
+            cents(300).pow(&cents(-100), mode, Rounding::Ceiling, MAX_BITS),
+            Ok(cents(34))
+        );
+        // -0.125
+        assert_eq!(
+            cents(-50).pow(&cents(300), mode, rounding, MAX_BITS),
+            Ok(cents(-12))
+        );
+        assert_eq!(
+            cents(-50).pow(&cents(300), mode, Rounding::Floor, MAX_BITS),
+            Ok(cents(-13))
+        );
+        assert_eq!(
+            cents(7).pow(&cents(0), mode, rounding, MAX_BITS),
+            Ok(cents(100))
+        );
+        assert_eq!(
+            cents(-100).pow(&cents(100_000_000_100), mode, rounding, MAX_BITS),
+            Ok(cents(-100))
+        );
+        assert_eq!(
+            cents(50).pow(&cents(50), mode, rounding, MAX_BITS),
+            Err(ErrorKind::UnsupportedOperation)
+        );
+        assert_eq!(
+            cents(0).pow(&cents(-100), mode, rounding, MAX_BITS),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(
+            cents(200).pow(&cents(20_000), mode, rounding, MAX_BITS),
+            Err(ErrorKind::Overflow)
+        );
+        // too large to compute exactly, but surely out of range or nearly nothing
+        let huge = cents(10_000_000);
+        assert_eq!(
+            cents(101).pow(&huge, mode, rounding, MAX_BITS),
+            Err(ErrorKind::Overflow)
+        );
+        assert_eq!(
+            cents(101).pow(&huge, ArithmeticMode::Saturating, rounding, MAX_BITS),
+            Ok(cents(i128::MAX))
+        );
+        assert_eq!(cents(50).pow(&huge, mode, rounding, MAX_BITS), Ok(cents(0)));
+        assert_eq!(
+            cents(50).pow(&huge, mode, Rounding::Ceiling, MAX_BITS),
+            Ok(cents(1))
+        );
+
+        // too large to compute exactly, yet in range
+        let units = Decimal::<4>::from_units;
+        let power = |base, exponent: i128, rounding| {
+            units(base).pow(&units(exponent * 10_000), mode, rounding, MAX_BITS)
+        };
+        // 1.0001^4681 is the largest computed exactly
+        assert_eq!(power(10_001, 4_681, rounding), Ok(units(15_969)));
+        assert_eq!(power(10_001, 4_682, rounding), Ok(units(15_971)));
+        assert_eq!(power(10_001, 5_000, rounding), Ok(units(16_487)));
+        assert_eq!(
+            power(10_001, 5_000, Rounding::TowardZero),
+            Ok(units(16_486))
+        );
+        assert_eq!(power(10_001, -5_000, rounding), Ok(units(6_065)));
+        assert_eq!(power(10_001, -5_000, Rounding::Ceiling), Ok(units(6_066)));
+        assert_eq!(power(-10_001, 5_001, rounding), Ok(units(-16_488)));
+        assert_eq!(power(-10_001, 5_001, Rounding::Floor), Ok(units(-16_489)));
+        // 1.01^9400 is above 10^40
+        assert_eq!(
+            cents(101).pow(&cents(940_000), mode, rounding, MAX_BITS),
+            Err(ErrorKind::Overflow)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Render an [`EvalError`] as a snippet of the expression
+//! with a caret pointing at the offending part
+//!
+//! Example:
+//! ```text
+//! error: unclosed parenthesis
+//!   |
+//! 1 | 3ae2
+//!   |   ^ missing `f` to close parenthesis opened here
+//! ```
+
+use crate::{ErrorKind, EvalError, Symbol, Syntax};
+use std::fmt;
+
+/// Human readable report of an error in the expression it came from
+pub struct Diagnostic<'a> {
+    error: &'a EvalError,
+    source: &'a str,
+    /// Syntax expression is written in.
+    /// Default letter syntax if not set
+    syntax: Option<&'a Syntax>,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub fn new(error: &'a EvalError, source: &'a str) -> Self {
+        Diagnostic {
+            error,
+            source,
+            syntax: None,
+        }
+    }
+
+    /// Refer to symbols of `syntax` in hints
+    pub fn syntax(mut self, syntax: &'a Syntax) -> Self {
+        self.syntax = Some(syntax);
+        self
+    }
+
+    /// Short suggestion printed next to the caret
+    pub fn hint(&self) -> String {
+        let default_syntax;
+        let syntax = match self.syntax {
+            Some(syntax) => syntax,
+            None => {
+                default_syntax = Syntax::default();
+                &default_syntax
+            }
+        };
+        let symbol = |symbol| syntax.text_of(symbol).unwrap_or("parenthesis").to_string();
+
+        match self.error.kind {
+            ErrorKind::UnexpectedCharacter(_) => {
+                "this character is not part of the expression syntax".to_string()
+            }
+            ErrorKind::UnclosedParenthesis => format!(
+                "missing `{}` to close parenthesis opened here",
+                symbol(Symbol::RParen)
+            ),
+            ErrorKind::UnmatchedParenthesis => {
+                format!("no `{}` opens this parenthesis", symbol(Symbol::LParen))
+            }
+            ErrorKind::MissingOperand => "expected a number here".to_string(),
+            ErrorKind::MissingOperator => "expected an operator before this".to_string(),
+            ErrorKind::InvalidAssignment => "only a variable can be assigned a value".to_string(),
+            ErrorKind::UndefinedVariable => {
+                let span = self.error.span;
+                let name = self.source.get(span.start..span.end).unwrap_or_default();
+                format!("`{name}` is not assigned any value")
+            }
+            ErrorKind::UnknownFunction => {
+                let span = self.error.span;
+                let name = self.source.get(span.start..span.end).unwrap_or_default();
+                format!("no function is named `{name}`")
+            }
+            ErrorKind::ArityMismatch { found, .. } => {
+                format!("this call passes {}", crate::functions::arguments(found))
+            }
+            ErrorKind::MissingElse => format!(
+                "missing `{}` with value if condition is false",
+                symbol(Symbol::Else)
+            ),
+            ErrorKind::UnmatchedElse => format!("no `{}` before this", symbol(Symbol::Then)),
+            ErrorKind::TypeMismatch { expected, found } => {
+                format!("this is a {found}, not a {expected}")
+            }
+            ErrorKind::UnexpectedSeparator => format!(
+                "`{}` only separates arguments of a function call",
+                symbol(Symbol::ArgSeparator)
+            ),
+            ErrorKind::MultipleStatements => "only a single statement is expected".to_string(),
+            ErrorKind::NestingTooDeep => "this parenthesis exceeds the nesting limit".to_string(),
+            ErrorKind::DivisionByZero => "right side of this division is zero".to_string(),
+            ErrorKind::NegativeShift => "right side of this shift is negative".to_string(),
+            ErrorKind::Overflow => "result of this operation does not fit".to_string(),
+            ErrorKind::UnsupportedOperation => {
+                "the number type can not compute this operation".to_string()
+            }
+            ErrorKind::LiteralOverflow => "this number can not be represented".to_string(),
+            ErrorKind::ResultTooLarge { .. } => {
+                "result of this operation exceeds the size limit".to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.error.span;
+        // error at end of input still points inside the source
+        let start = span.start.min(self.source.len());
+
+        // locate the line containing the error
+        let line_start = self.source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[start..]
+            .find('\n')
+            .map_or(self.source.len(), |i| start + i);
+        let line = &self.source[line_start..line_end];
+        let line_number = self.source[..line_start].matches('\n').count() + 1;
+
+        // columns are counted in chars so multibyte input lines up
+        let column = self.source[line_start..start].chars().count();
+        let width = self.source[start..span.end.clamp(start, line_end)]
+            .chars()
+            .count()
+            .max(1);
+
+        let gutter = " ".repeat(line_number.to_string().len());
+        writeln!(f, "error: {}", self.error.kind)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_number} | {line}")?;
+        write!(
+            f,
+            "{gutter} | {}{} {}",
+            " ".repeat(column),
+            "^".repeat(width),
+            self.hint()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compute, EvalOptions, Evaluator, Syntax};
+
+    fn render(expression: &str) -> String {
+        compute(expression)
+            .unwrap_err()
+            .diagnostic(expression)
+            .to_string()
+    }
+
+    #[test]
+    fn unclosed_parenthesis() {
+        assert_eq!(
+            render("3ae2"),
+            "error: unclosed parenthesis\n  |\n1 | 3ae2\n  |   ^ missing `f` to close parenthesis opened here"
+        );
+    }
+
+    #[test]
+    fn hint_uses_syntax() {
+        let syntax = Syntax::standard();
+        let error = Evaluator::with_options(EvalOptions::new().syntax(syntax.clone()))
+            .evaluate("1 + (2")
+            .unwrap_err();
+        assert_eq!(
+            error.diagnostic("1 + (2").syntax(&syntax).to_string(),
+            "error: unclosed parenthesis\n  |\n1 | 1 + (2\n  |     ^ missing `)` to close parenthesis opened here"
+        );
+    }
+
+    #[test]
+    fn error_at_end_of_input() {
+        assert_eq!(
+            render("3 a"),
+            "error: missing operand\n  |\n1 | 3 a\n  |    ^ expected a number here"
+        );
+    }
+
+    #[test]
+    fn points_at_offending_line() {
+        assert_eq!(
+            render("1 a 2\nc 3 @"),
+            "error: unexpected character '@'\n  |\n2 | c 3 @\n  |     ^ this character is not part of the expression syntax"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::Value;
+use std::collections::HashMap;
+
+/// Variables assigned while evaluating expressions
+///
+/// Pass the same environment to several evaluations
+/// to reuse values assigned in earlier ones.
+///
+/// Example:
+/// ```
+/// use parser_rs::{Environment, Evaluator, Value};
+///
+/// let evaluator = Evaluator::new();
+/// let mut env = Environment::new();
+/// assert_eq!(evaluator.evaluate_in("x = 3a2", &mut env), Ok(5));
+/// assert_eq!(evaluator.evaluate_in("x c 4", &mut env), Ok(20));
+/// assert_eq!(env.get("x"), Some(Value::Number(5)));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Environment<N = i128> {
+    variables: HashMap<String, Value<N>>,
+}
+
+impl<N> Default for Environment<N> {
+    fn default() -> Self {
+        Environment {
+            variables: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Clone> Environment<N> {
+    pub fn new() -> Self {
+        Environment::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value<N>> {
+        self.variables.get(name).cloned()
+    }
+
+    /// Assign `value` to variable `name`
+    /// Returns the previous value if there was one
+    pub fn set(&mut self, name: impl Into<String>, value: Value<N>) -> Option<Value<N>> {
+        self.variables.insert(name.into(), value)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value<N>> {
+        self.variables.remove(name)
+    }
+
+    /// Every variable with its value, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Value<N>)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+    }
+}
@@ -0,0 +1,124 @@
+use crate::diagnostic::Diagnostic;
+use crate::{Arity, Span, Type};
+use std::fmt;
+
+/// What went wrong while evaluating an expression
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// Character is not part of the syntax
+    UnexpectedCharacter(char),
+    /// Open parenthesis was never closed
+    UnclosedParenthesis,
+    /// Close parenthesis without matching open parenthesis
+    UnmatchedParenthesis,
+    /// Operator is not followed by a number
+    MissingOperand,
+    /// Two operands without an operator in between
+    MissingOperator,
+    /// Left side of assignment is not a variable
+    InvalidAssignment,
+    /// Variable used before any value is assigned to it
+    UndefinedVariable,
+    /// Call of a function that is not registered
+    UnknownFunction,
+    /// Function called with a number of arguments it does not accept
+    ArityMismatch {
+        expected: Arity,
+        found: usize,
+    },
+    /// `Symbol::ArgSeparator` outside of a function call
+    UnexpectedSeparator,
+    /// `Symbol::Then` without matching `Symbol::Else`
+    MissingElse,
+    /// `Symbol::Else` without matching `Symbol::Then`
+    UnmatchedElse,
+    /// Value of one type used where another is expected,
+    /// like a bool added to a number
+    TypeMismatch {
+        expected: Type,
+        found: Type,
+    },
+    /// `Symbol::EndStatement` where only a single statement is expected
+    MultipleStatements,
+    /// More parenthesis open at once than `EvalOptions::max_depth` allows
+    NestingTooDeep,
+    DivisionByZero,
+    /// Bit shift by a negative amount
+    NegativeShift,
+    /// Result does not fit in the number type
+    Overflow,
+    /// Operation the number type does not support, like a bit shift of floats
+    UnsupportedOperation,
+    /// Number written in the expression does not fit in the number type
+    LiteralOverflow,
+    /// Power or shift of a number type without a fixed size
+    /// longer than `EvalOptions::max_bits` allows
+    ResultTooLarge {
+        max_bits: usize,
+    },
+}
+
+/// Error returned when an expression can not be evaluated
+///
+/// `span` is the part of the expression where error occured
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EvalError {
+    pub kind: ErrorKind,
+    pub span: Span,
+}
+
+impl EvalError {
+    pub fn new(kind: ErrorKind, span: Span) -> Self {
+        EvalError { kind, span }
+    }
+
+    /// Byte offset in the expression where error occured
+    pub fn offset(&self) -> usize {
+        self.span.start
+    }
+
+    /// Render this error against the expression it came from
+    pub fn diagnostic<'a>(&'a self, source: &'a str) -> Diagnostic<'a> {
+        Diagnostic::new(self, source)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedCharacter(ch) => write!(f, "unexpected character {ch:?}"),
+            ErrorKind::UnclosedParenthesis => write!(f, "unclosed parenthesis"),
+            ErrorKind::UnmatchedParenthesis => write!(f, "unmatched close parenthesis"),
+            ErrorKind::MissingOperand => write!(f, "missing operand"),
+            ErrorKind::MissingOperator => write!(f, "missing operator"),
+            ErrorKind::InvalidAssignment => write!(f, "invalid assignment"),
+            ErrorKind::UndefinedVariable => write!(f, "undefined variable"),
+            ErrorKind::UnknownFunction => write!(f, "unknown function"),
+            ErrorKind::ArityMismatch { expected, found } => {
+                write!(f, "function takes {expected} but {found} given")
+            }
+            ErrorKind::UnexpectedSeparator => write!(f, "argument separator outside of call"),
+            ErrorKind::MissingElse => write!(f, "condition without else branch"),
+            ErrorKind::UnmatchedElse => write!(f, "else branch without condition"),
+            ErrorKind::TypeMismatch { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            ErrorKind::MultipleStatements => write!(f, "more than one statement"),
+            ErrorKind::NestingTooDeep => write!(f, "parenthesis nested too deep"),
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ErrorKind::NegativeShift => write!(f, "negative shift amount"),
+            ErrorKind::Overflow => write!(f, "arithmetic overflow"),
+            ErrorKind::UnsupportedOperation => write!(f, "unsupported operation"),
+            ErrorKind::LiteralOverflow => write!(f, "number literal too large"),
+            ErrorKind::ResultTooLarge { max_bits } => write!(f, "result exceeds {max_bits} bits"),
+        }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset())
+    }
+}
+
+impl std::error::Error for EvalError {}
@@ -0,0 +1,395 @@
+use crate::functions::Functions;
+use crate::{
+    Arity, Environment, ErrorKind, EvalError, EvalOptions, Expr, Number, Operator, Parser, Span,
+    UnaryOperator, Value,
+};
+use std::mem;
+
+/// Unit of work while walking the expression tree
+enum Step<'e, N> {
+    /// Compute operands of this node
+    Visit(&'e Expr<N>),
+    /// Operands are computed, apply the operator of this node
+    Apply(&'e Expr<N>),
+    /// Left side or condition is computed, pick what to compute next
+    Branch(&'e Expr<N>),
+}
+
+/// Evaluates expressions written in the parser-rs syntax
+///
+/// Functions `max`, `min`, `abs`, `pow` and `gcd` are available by default.
+/// Names of all functions are reserved in the syntax of the evaluator
+///
+/// Numbers are `i128` unless another [`Number`] type is picked
+/// with [`Evaluator::with_backend`]
+#[derive(Clone, Debug)]
+pub struct Evaluator<N = i128> {
+    options: EvalOptions,
+    functions: Functions<N>,
+}
+
+impl<N: Number> Default for Evaluator<N> {
+    fn default() -> Self {
+        Evaluator::with_backend(EvalOptions::default())
+    }
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        Evaluator::default()
+    }
+
+    pub fn with_options(options: EvalOptions) -> Self {
+        Evaluator::with_backend(options)
+    }
+}
+
+impl<N: Number> Evaluator<N> {
+    /// Evaluator computing with numbers of type `N`
+    pub fn with_backend(mut options: EvalOptions) -> Self {
+        let functions = Functions::builtin();
+        for name in functions.names() {
+            options.syntax = mem::take(&mut options.syntax).with_name(name);
+        }
+        Evaluator { options, functions }
+    }
+
+    /// Make `function` callable from expressions as `name`,
+    /// replacing any function already named so
+    ///
+    /// `function` is only called with a number of arguments `arity` accepts.
+    /// Error it returns is reported at the call
+    pub fn with_function(
+        mut self,
+        name: impl Into<String>,
+        arity: Arity,
+        function: impl Fn(&[N]) -> Result<N, ErrorKind> + Send + Sync + 'static,
+    ) -> Self {
+        let name = name.into();
+        self.options.syntax = mem::take(&mut self.options.syntax).with_name(name.as_str());
+        self.functions = self.functions.with(name, arity, function);
+        self
+    }
+
+    pub fn options(&self) -> &EvalOptions {
+        &self.options
+    }
+
+    /// Build syntax tree of given single statement expression without computing it
+    pub fn parse(&self, raw_expression: &str) -> Result<Expr<N>, EvalError> {
+        Parser::with_backend(raw_expression, &self.options).parse()
+    }
+
+    /// Build syntax tree of every statement of given expression.
+    /// Empty statements are left out
+    pub fn parse_all(&self, raw_expression: &str) -> Vec<Result<Expr<N>, EvalError>> {
+        let mut parser = Parser::with_backend(raw_expression, &self.options);
+        std::iter::from_fn(|| parser.next_statement()).collect()
+    }
+
+    /// Compute the numeric result of a parsed expression
+    /// without any variable defined beforehand
+    pub fn eval(&self, expr: &Expr<N>) -> Result<N, EvalError> {
+        self.eval_in(expr, &mut Environment::new())
+    }
+
+    /// Compute the numeric result of a parsed expression
+    /// reading and assigning variables of `env`
+    pub fn eval_in(&self, expr: &Expr<N>, env: &mut Environment<N>) -> Result<N, EvalError> {
+        self.eval_value_in(expr, env)?.number(expr)
+    }
+
+    /// Compute the result of a parsed expression, number or bool,
+    /// reading and assigning variables of `env`
+    ///
+    /// Tree is walked with an explicit stack,
+    /// so no depth of expression can overflow the call stack
+    pub fn eval_value_in(
+        &self,
+        expr: &Expr<N>,
+        env: &mut Environment<N>,
+    ) -> Result<Value<N>, EvalError> {
+        let mode = self.options.arithmetic;
+        let mut steps = vec![Step::Visit(expr)];
+        let mut values: Vec<Value<N>> = vec![];
+        let pop = |values: &mut Vec<Value<N>>| values.pop().expect("value of visited operand");
+
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Visit(expr) => match expr {
+                    Expr::Num { value, .. } => values.push(Value::Number(value.clone())),
+                    Expr::Var { name, span } => values.push(
+                        env.get(name)
+                            .ok_or(EvalError::new(ErrorKind::UndefinedVariable, *span))?,
+                    ),
+                    Expr::Group { inner, .. } => steps.push(Step::Visit(inner)),
+                    Expr::Neg { operand: child, .. }
+                    | Expr::Unary { operand: child, .. }
+                    | Expr::Assign { value: child, .. } => {
+                        steps.push(Step::Apply(expr));
+                        steps.push(Step::Visit(child));
+                    }
+                    Expr::BinOp {
+                        op: Operator::And | Operator::Or,
+                        lhs,
+                        ..
+                    }
+                    | Expr::Conditional { condition: lhs, .. } => {
+                        // right side depends on the left one
+                        steps.push(Step::Branch(expr));
+                        steps.push(Step::Visit(lhs));
+                    }
+                    Expr::BinOp { lhs, rhs, .. } => {
+                        // lhs is computed first and so is deeper in `values`
+                        steps.push(Step::Apply(expr));
+                        steps.push(Step::Visit(rhs));
+                        steps.push(Step::Visit(lhs));
+                    }
+                    Expr::Call {
+                        name,
+                        name_span,
+                        args,
+                        span,
+                    } => {
+                        // check the call before computing any argument
+                        let function = self
+                            .functions
+                            .get(name)
+                            .ok_or(EvalError::new(ErrorKind::UnknownFunction, *name_span))?;
+                        if !function.arity.accepts(args.len()) {
+                            let kind = ErrorKind::ArityMismatch {
+                                expected: function.arity,
+                                found: args.len(),
+                            };
+                            return Err(EvalError::new(kind, *span));
+                        }
+                        steps.push(Step::Apply(expr));
+                        steps.extend(args.iter().rev().map(Step::Visit));
+                    }
+                },
+
+                Step::Branch(expr) => match expr {
+                    Expr::BinOp { op, lhs, rhs, .. } => {
+                        let lhs = pop(&mut values).bool(lhs)?;
+                        // `false && rhs` and `true || rhs` are decided without `rhs`
+                        if lhs == (*op == Operator::Or) {
+                            values.push(Value::Bool(lhs));
+                        } else {
+                            steps.push(Step::Apply(expr));
+                            steps.push(Step::Visit(rhs));
+                        }
+                    }
+                    Expr::Conditional {
+                        condition,
+                        then,
+                        otherwise,
+                    } => {
+                        let branch = match pop(&mut values).bool(condition)? {
+                            true => then,
+                            false => otherwise,
+                        };
+                        steps.push(Step::Visit(branch));
+                    }
+                    _ => unreachable!("only logical operators and conditions branch"),
+                },
+
+                Step::Apply(Expr::Call {
+                    name, args, span, ..
+                }) => {
+                    let arg_values = values.split_off(values.len() - args.len());
+                    let args = args
+                        .iter()
+                        .zip(arg_values)
+                        .map(|(arg, value)| value.number(arg))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let function = self.functions.get(name).expect("function checked on visit");
+                    let result = function
+                        .call(&args, &self.options)
+                        .map_err(|kind| EvalError::new(kind, *span))?;
+                    values.push(Value::Number(result));
+                }
+                Step::Apply(expr) => {
+                    let rhs = pop(&mut values);
+                    let result = match expr {
+                        Expr::Neg { operand, op_span } => Value::Number(
+                            rhs.number(operand)?
+                                .neg(mode)
+                                .map_err(|kind| EvalError::new(kind, *op_span))?,
+                        ),
+                        // left side is already checked to be a bool
+                        Expr::BinOp {
+                            op: Operator::And | Operator::Or,
+                            rhs: rhs_expr,
+                            ..
+                        } => Value::Bool(rhs.bool(rhs_expr)?),
+                        Expr::BinOp {
+                            op,
+                            op_span,
+                            lhs: lhs_expr,
+                            rhs: rhs_expr,
+                        } => {
+                            let lhs = pop(&mut values);
+                            match (&lhs, op) {
+                                // bools can only be compared for equality
+                                (Value::Bool(lhs), Operator::Eq | Operator::Ne) => {
+                                    let rhs = rhs.bool(rhs_expr)?;
+                                    Value::Bool((*lhs == rhs) == (*op == Operator::Eq))
+                                }
+                                _ => self.apply_operator(
+                                    lhs.number(lhs_expr)?,
+                                    *op,
+                                    rhs.number(rhs_expr)?,
+                                    *op_span,
+                                )?,
+                            }
+                        }
+                        Expr::Unary {
+                            op: UnaryOperator::BitNot,
+                            op_span,
+                            operand,
+                        } => Value::Number(
+                            rhs.number(operand)?
+                                .bit_not()
+                                .map_err(|kind| EvalError::new(kind, *op_span))?,
+                        ),
+                        Expr::Unary {
+                            op: UnaryOperator::Not,
+                            operand,
+                            ..
+                        } => Value::Bool(!rhs.bool(operand)?),
+                        Expr::Assign { name, .. } => {
+                            env.set(name.as_str(), rhs.clone());
+                            rhs
+                        }
+                        _ => unreachable!("only operators are applied"),
+                    };
+                    values.push(result);
+                }
+            }
+        }
+
+        Ok(values.pop().expect("value of the whole expression"))
+    }
+
+    /// Compute the numeric result of given expression
+    ///
+    /// If expression has several statements, every one of them is computed
+    /// and result of the last one is returned
+    pub fn evaluate(&self, raw_expression: &str) -> Result<N, EvalError> {
+        self.evaluate_in(raw_expression, &mut Environment::new())
+    }
+
+    /// Same as [`Evaluator::evaluate`], reading and assigning variables of `env`
+    pub fn evaluate_in(
+        &self,
+        raw_expression: &str,
+        env: &mut Environment<N>,
+    ) -> Result<N, EvalError> {
+        match self.evaluate_statements(raw_expression, env)? {
+            (result, Some(statement)) => result.number(&statement),
+            (result, None) => Ok(result.as_number().expect("empty expression is 0")),
+        }
+    }
+
+    /// Compute the result of given expression, number or bool,
+    /// reading and assigning variables of `env`
+    ///
+    /// Example:
+    /// ```
+    /// use parser_rs::{Environment, Evaluator, Value};
+    ///
+    /// let mut env = Environment::new();
+    /// env.set("x", Value::Number(12));
+    /// let evaluator = Evaluator::new();
+    /// assert_eq!(evaluator.evaluate_value_in("x > 10 && x < 20", &mut env), Ok(Value::Bool(true)));
+    /// assert_eq!(evaluator.evaluate_value_in("x h 2 == 0 ? x : 0", &mut env), Ok(Value::Number(12)));
+    /// ```
+    pub fn evaluate_value_in(
+        &self,
+        raw_expression: &str,
+        env: &mut Environment<N>,
+    ) -> Result<Value<N>, EvalError> {
+        let (result, _) = self.evaluate_statements(raw_expression, env)?;
+        Ok(result)
+    }
+
+    /// Result of the last statement along with that statement
+    fn evaluate_statements(
+        &self,
+        raw_expression: &str,
+        env: &mut Environment<N>,
+    ) -> Result<(Value<N>, Option<Expr<N>>), EvalError> {
+        let mut parser = Parser::with_backend(raw_expression, &self.options);
+        let mut result = (Value::Number(N::zero()), None);
+        while let Some(statement) = parser.next_statement() {
+            let statement = statement?;
+            result = (self.eval_value_in(&statement, env)?, Some(statement));
+        }
+        Ok(result)
+    }
+
+    /// Compute every statement of given expression independently.
+    /// Empty statements are left out
+    ///
+    /// Example:
+    /// ```
+    /// use parser_rs::{ErrorKind, Evaluator};
+    ///
+    /// let results = Evaluator::new().evaluate_all("3a2; 5c4; 1d0");
+    /// assert_eq!(results[0], Ok(5));
+    /// assert_eq!(results[1], Ok(20));
+    /// assert_eq!(results[2].map_err(|e| e.kind), Err(ErrorKind::DivisionByZero));
+    /// ```
+    pub fn evaluate_all(&self, raw_expression: &str) -> Vec<Result<N, EvalError>> {
+        self.evaluate_all_in(raw_expression, &mut Environment::new())
+    }
+
+    /// Same as [`Evaluator::evaluate_all`], reading and assigning variables of `env`
+    pub fn evaluate_all_in(
+        &self,
+        raw_expression: &str,
+        env: &mut Environment<N>,
+    ) -> Vec<Result<N, EvalError>> {
+        self.parse_all(raw_expression)
+            .into_iter()
+            .map(|statement| self.eval_in(&statement?, env))
+            .collect()
+    }
+
+    /// Compute `lhs operator rhs`
+    /// `span` is where operator appeared in the expression
+    fn apply_operator(
+        &self,
+        lhs: N,
+        operator: Operator,
+        rhs: N,
+        span: Span,
+    ) -> Result<Value<N>, EvalError> {
+        let (mode, rounding) = (self.options.arithmetic, self.options.rounding);
+        let result = match operator {
+            Operator::Lt => return Ok(Value::Bool(lhs < rhs)),
+            Operator::Le => return Ok(Value::Bool(lhs <= rhs)),
+            Operator::Gt => return Ok(Value::Bool(lhs > rhs)),
+            Operator::Ge => return Ok(Value::Bool(lhs >= rhs)),
+            Operator::Eq => return Ok(Value::Bool(lhs == rhs)),
+            Operator::Ne => return Ok(Value::Bool(lhs != rhs)),
+            Operator::And | Operator::Or => unreachable!("logical operators take bools"),
+            Operator::Add => lhs.add(&rhs, mode),
+            Operator::Sub => lhs.sub(&rhs, mode),
+            Operator::Mul => lhs.mul(&rhs, mode, rounding),
+            Operator::Div => lhs.div(&rhs, mode, rounding),
+            Operator::FloorDiv => lhs.div_floor(&rhs, mode),
+            Operator::Rem => lhs.rem(&rhs, mode),
+            Operator::Mod => lhs.rem_euclid(&rhs, mode),
+            Operator::Pow => lhs.pow(&rhs, mode, rounding, self.options.max_bits),
+            Operator::BitAnd => lhs.bit_and(&rhs),
+            Operator::BitOr => lhs.bit_or(&rhs),
+            Operator::BitXor => lhs.bit_xor(&rhs),
+            Operator::Shl => lhs.shl(&rhs, mode, self.options.max_bits),
+            Operator::Shr => lhs.shr(&rhs),
+        };
+        result
+            .map(Value::Number)
+            .map_err(|kind| EvalError::new(kind, span))
+    }
+}
@@ -0,0 +1,273 @@
+//! Write results out in another radix, with grouped digits,
+//! in scientific notation or as a fraction
+//!
+//! Example:
+//! ```
+//! use parser_rs::{compute, Notation, NumberFormat, Rational};
+//!
+//! let result = compute("255 c 4112").unwrap();
+//! assert_eq!(NumberFormat::new().grouping(true).format(&result), Ok("1,048,560".to_string()));
+//! assert_eq!(NumberFormat::new().radix(16).format(&result), Ok("0xFFFF0".to_string()));
+//! assert_eq!(
+//!     NumberFormat::new().radix(2).grouping(true).format(&-10i128),
+//!     Ok("-0b1010".to_string())
+//! );
+//!
+//! let scientific = NumberFormat::new().notation(Notation::Scientific).precision(2);
+//! assert_eq!(scientific.format(&1234.5), Ok("1.23e3".to_string()));
+//! let third = Rational::new(1, 3).unwrap();
+//! assert_eq!(NumberFormat::new().precision(3).format(&third), Ok("0.333".to_string()));
+//! assert_eq!(NumberFormat::new().notation(Notation::Fraction).format(&third), Ok("1/3".to_string()));
+//! ```
+
+use crate::lexer::RADIX;
+use crate::{ErrorKind, Number, Value};
+
+/// Way a number is written out
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Notation {
+    /// As the number type displays itself, in any radix for integers
+    #[default]
+    Plain,
+    /// Mantissa and exponent like `1.5e3`, for floats
+    Scientific,
+    /// Exact fraction like `7/2`, for rationals, decimals and integers
+    Fraction,
+}
+
+/// Settings to write results with
+///
+/// Numbers that can not be written as asked, like a fraction in hex,
+/// fail with `ErrorKind::UnsupportedOperation`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NumberFormat {
+    /// Radix of integers written in `Notation::Plain`.
+    /// 16, 8 and 2 are prefixed by `0x`, `0o` and `0b`
+    pub radix: u32,
+    /// Separate groups of integer digits, by `,` every 3 digits in radix 10
+    /// and by `_` every 4 digits in other radices
+    pub grouping: bool,
+    pub notation: Notation,
+    /// Digits after the point, for number types that have a fraction
+    pub precision: Option<usize>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            radix: RADIX,
+            grouping: false,
+            notation: Notation::default(),
+            precision: None,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn new() -> Self {
+        NumberFormat::default()
+    }
+
+    /// Panics if `radix` is not in `2..=36`
+    pub fn radix(mut self, radix: u32) -> Self {
+        assert!((2..=36).contains(&radix), "Invalid radix: {radix}");
+        self.radix = radix;
+        self
+    }
+
+    pub fn grouping(mut self, grouping: bool) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    pub fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// `number` written out with these settings
+    pub fn format<N: Number>(&self, number: &N) -> Result<String, ErrorKind> {
+        let text = match (self.notation, self.radix) {
+            (Notation::Plain, RADIX) => match self.precision {
+                Some(precision) => format!("{number:.precision$}"),
+                None => number.to_string(),
+            },
+            (Notation::Plain, radix) => {
+                let (negative, digits) = number
+                    .to_radix(radix)
+                    .ok_or(ErrorKind::UnsupportedOperation)?;
+                let sign = if negative { "-" } else { "" };
+                let prefix = match radix {
+                    16 => "0x",
+                    8 => "0o",
+                    2 => "0b",
+                    _ => "",
+                };
+                return Ok(format!("{sign}{prefix}{}", self.group(&digits)));
+            }
+            (_, radix) if radix != RADIX => return Err(ErrorKind::UnsupportedOperation),
+            (Notation::Scientific, _) => number
+                .to_scientific(self.precision)
+                .ok_or(ErrorKind::UnsupportedOperation)?,
+            (Notation::Fraction, _) => number
+                .to_fraction()
+                .ok_or(ErrorKind::UnsupportedOperation)?,
+        };
+        // numerator and denominator are grouped apart
+        let parts = text.split('/').map(|part| self.group(part));
+        Ok(parts.collect::<Vec<_>>().join("/"))
+    }
+
+    /// `value` written out with these settings, bools as `true` or `false`
+    pub fn format_value<N: Number>(&self, value: &Value<N>) -> Result<String, ErrorKind> {
+        match value {
+            Value::Number(number) => self.format(number),
+            Value::Bool(bool) => Ok(bool.to_string()),
+        }
+    }
+
+    /// `text` with separators between groups of its leading integer digits
+    fn group(&self, text: &str) -> String {
+        if !self.grouping {
+            return text.to_string();
+        }
+        let (separator, size) = match self.radix {
+            RADIX => (',', 3),
+            _ => ('_', 4),
+        };
+        let sign = usize::from(text.starts_with('-'));
+        let len = text[sign..]
+            .find(|ch: char| !ch.is_digit(self.radix))
+            .map_or(text.len(), |len| sign + len);
+
+        let mut grouped = text[..sign].to_string();
+        for (index, digit) in text[sign..len].chars().enumerate() {
+            if index > 0 && (len - sign - index) % size == 0 {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped + &text[len..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BigInt, Decimal, Rational};
+
+    #[test]
+    fn radix() {
+        let hex = NumberFormat::new().radix(16);
+        assert_eq!(hex.format(&-31i64), Ok("-0x1F".to_string()));
+        assert_eq!(hex.format(&u64::MAX), Ok("0xFFFFFFFFFFFFFFFF".to_string()));
+        assert_eq!(
+            hex.format(&i128::MIN),
+            Ok(format!("-0x8{}", "0".repeat(31)))
+        );
+        assert_eq!(
+            NumberFormat::new().radix(8).format(&8i128),
+            Ok("0o10".to_string())
+        );
+        assert_eq!(
+            NumberFormat::new().radix(36).format(&35i128),
+            Ok("Z".to_string())
+        );
+        assert_eq!(hex.format(&BigInt::from(-255)), Ok("-0xFF".to_string()));
+        assert_eq!(hex.format(&BigInt::from(0)), Ok("0x0".to_string()));
+        assert_eq!(hex.format(&Rational::from(16)), Ok("0x10".to_string()));
+        assert_eq!(
+            hex.format(&Decimal::<2>::from_units(1600)),
+            Ok("0x10".to_string())
+        );
+        assert_eq!(hex.format(&255.0), Ok("0xFF".to_string()));
+
+        // only integers
+        let error = Err(ErrorKind::UnsupportedOperation);
+        assert_eq!(hex.format(&0.5), error);
+        assert_eq!(hex.format(&Rational::new(1, 2).unwrap()), error);
+        assert_eq!(hex.format(&Decimal::<2>::from_units(150)), error);
+        assert_eq!(hex.notation(Notation::Fraction).format(&1i128), error);
+    }
+
+    #[test]
+    fn grouping() {
+        let grouped = NumberFormat::new().grouping(true);
+        assert_eq!(grouped.format(&-1234567i128), Ok("-1,234,567".to_string()));
+        assert_eq!(grouped.format(&123i128), Ok("123".to_string()));
+        assert_eq!(grouped.format(&1234.5), Ok("1,234.5".to_string()));
+        assert_eq!(
+            grouped.format(&Decimal::<2>::from_units(123456789)),
+            Ok("1,234,567.89".to_string())
+        );
+        assert_eq!(
+            grouped.clone().radix(2).format(&-1023i128),
+            Ok("-0b11_1111_1111".to_string())
+        );
+        assert_eq!(
+            grouped
+                .clone()
+                .notation(Notation::Fraction)
+                .format(&Rational::new(1000, 10001).unwrap()),
+            Ok("1,000/10,001".to_string())
+        );
+        assert_eq!(
+            grouped.notation(Notation::Scientific).format(&-12345.0),
+            Ok("-1.2345e4".to_string())
+        );
+    }
+
+    #[test]
+    fn notations() {
+        let scientific = NumberFormat::new().notation(Notation::Scientific);
+        assert_eq!(scientific.format(&0.00025), Ok("2.5e-4".to_string()));
+        assert_eq!(
+            scientific.clone().precision(1).format(&-1250.0),
+            Ok("-1.2e3".to_string())
+        );
+        assert_eq!(
+            scientific.format(&3i128),
+            Err(ErrorKind::UnsupportedOperation)
+        );
+
+        let fraction = NumberFormat::new().notation(Notation::Fraction);
+        assert_eq!(
+            fraction.format(&Rational::new(-7, 2).unwrap()),
+            Ok("-7/2".to_string())
+        );
+        assert_eq!(
+            fraction.format(&Decimal::<2>::from_units(-125)),
+            Ok("-5/4".to_string())
+        );
+        assert_eq!(fraction.format(&12i128), Ok("12".to_string()));
+        assert_eq!(fraction.format(&0.5), Err(ErrorKind::UnsupportedOperation));
+
+        let precise = NumberFormat::new().precision(2);
+        assert_eq!(
+            precise.format(&Rational::new(2, 3).unwrap()),
+            Ok("0.67".to_string())
+        );
+        assert_eq!(precise.format(&0.126), Ok("0.13".to_string()));
+        assert_eq!(precise.format(&7i128), Ok("7".to_string()));
+        assert_eq!(
+            precise.format(&Decimal::<4>::from_units(3333)),
+            Ok("0.33".to_string())
+        );
+        assert_eq!(
+            NumberFormat::new()
+                .precision(4)
+                .format(&Decimal::<2>::from_units(33)),
+            Ok("0.3300".to_string())
+        );
+
+        assert_eq!(
+            NumberFormat::new().format_value(&Value::<i128>::Bool(true)),
+            Ok("true".to_string())
+        );
+    }
+}
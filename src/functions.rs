@@ -0,0 +1,251 @@
+//! Functions callable from expressions, like `max e3, 7f`
+//!
+//! Example:
+//! ```
+//! use parser_rs::{Arity, ErrorKind, EvalOptions, Evaluator, Syntax};
+//!
+//! let evaluator = Evaluator::with_options(EvalOptions::new().syntax(Syntax::standard()))
+//!     .with_function("double", Arity::Exact(1), |args| {
+//!         args[0].checked_mul(2).ok_or(ErrorKind::Overflow)
+//!     });
+//! assert_eq!(evaluator.evaluate("max(3, 7) + double(abs(-4))"), Ok(15));
+//! ```
+
+use crate::{ArithmeticMode, ErrorKind, EvalOptions, Number};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Number of arguments a function accepts
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(self, count: usize) -> bool {
+        match self {
+            Arity::Exact(arity) => count == arity,
+            Arity::AtLeast(arity) => count >= arity,
+        }
+    }
+}
+
+/// `count` followed by the word argument in the right form
+pub(crate) fn arguments(count: usize) -> String {
+    match count {
+        1 => "1 argument".to_string(),
+        _ => format!("{count} arguments"),
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arity::Exact(arity) => write!(f, "{}", arguments(*arity)),
+            Arity::AtLeast(arity) => write!(f, "at least {}", arguments(*arity)),
+        }
+    }
+}
+
+type Body<N> = dyn Fn(&[N], &EvalOptions) -> Result<N, ErrorKind> + Send + Sync;
+
+/// Function registered under a name
+/// Its body is only called with a number of arguments `arity` accepts,
+/// and with options of the evaluator calling it
+pub(crate) struct Function<N> {
+    pub arity: Arity,
+    body: Arc<Body<N>>,
+}
+
+impl<N> Clone for Function<N> {
+    fn clone(&self) -> Self {
+        Function {
+            arity: self.arity,
+            body: Arc::clone(&self.body),
+        }
+    }
+}
+
+impl<N> Function<N> {
+    pub fn call(&self, args: &[N], options: &EvalOptions) -> Result<N, ErrorKind> {
+        (self.body)(args, options)
+    }
+}
+
+/// Functions known to an evaluator, by name
+pub(crate) struct Functions<N> {
+    functions: HashMap<String, Function<N>>,
+}
+
+impl<N> Clone for Functions<N> {
+    fn clone(&self) -> Self {
+        Functions {
+            functions: self.functions.clone(),
+        }
+    }
+}
+
+impl<N: Number> Default for Functions<N> {
+    fn default() -> Self {
+        Functions::builtin()
+    }
+}
+
+impl<N> fmt::Debug for Functions<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.functions
+                    .iter()
+                    .map(|(name, function)| (name, function.arity)),
+            )
+            .finish()
+    }
+}
+
+impl<N: Number> Functions<N> {
+    /// `max`, `min`, `abs`, `pow` and `gcd`
+    ///
+    /// `abs` and `pow` compute in the arithmetic mode and rounding of the evaluator
+    pub fn builtin() -> Self {
+        Functions {
+            functions: HashMap::new(),
+        }
+        .with("max", Arity::AtLeast(1), |args| {
+            Ok(extreme(args, |max, arg| arg > max))
+        })
+        .with("min", Arity::AtLeast(1), |args| {
+            Ok(extreme(args, |min, arg| arg < min))
+        })
+        .with_options("abs", Arity::Exact(1), |args, options| {
+            abs(&args[0], options.arithmetic)
+        })
+        .with_options("pow", Arity::Exact(2), |args, options| {
+            pow(&args[0], &args[1], options)
+        })
+        .with("gcd", Arity::Exact(2), |args| gcd(&args[0], &args[1]))
+    }
+
+    pub fn with(
+        self,
+        name: impl Into<String>,
+        arity: Arity,
+        body: impl Fn(&[N]) -> Result<N, ErrorKind> + Send + Sync + 'static,
+    ) -> Self {
+        self.with_options(name, arity, move |args, _| body(args))
+    }
+
+    /// Same as `with`, for a `body` that depends on options of the evaluator
+    pub fn with_options(
+        mut self,
+        name: impl Into<String>,
+        arity: Arity,
+        body: impl Fn(&[N], &EvalOptions) -> Result<N, ErrorKind> + Send + Sync + 'static,
+    ) -> Self {
+        let function = Function {
+            arity,
+            body: Arc::new(body),
+        };
+        self.functions.insert(name.into(), function);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Function<N>> {
+        self.functions.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str)
+    }
+}
+
+/// First of `args` no other one `replaces`
+fn extreme<N: Number>(args: &[N], replaces: impl Fn(&N, &N) -> bool) -> N {
+    let (first, rest) = args.split_first().expect("at least one argument");
+    rest.iter()
+        .fold(
+            first,
+            |best, arg| if replaces(best, arg) { arg } else { best },
+        )
+        .clone()
+}
+
+/// Negation of negative `number` follows `mode`, like the `-` operator does
+fn abs<N: Number>(number: &N, mode: ArithmeticMode) -> Result<N, ErrorKind> {
+    match *number < N::zero() {
+        true => number.neg(mode),
+        false => Ok(number.clone()),
+    }
+}
+
+/// `base` raised to `exponent`, same as the power operator
+/// Negative exponent rounds the fraction like division does
+fn pow<N: Number>(base: &N, exponent: &N, options: &EvalOptions) -> Result<N, ErrorKind> {
+    let (mode, rounding) = (options.arithmetic, options.rounding);
+    base.pow(exponent, mode, rounding, options.max_bits)
+}
+
+/// Greatest common divisor, always positive
+fn gcd<N: Number>(lhs: &N, rhs: &N) -> Result<N, ErrorKind> {
+    let mode = ArithmeticMode::Checked;
+    let (mut lhs, mut rhs) = (abs(lhs, mode)?, abs(rhs, mode)?);
+    while rhs != N::zero() {
+        let rem = lhs.rem(&rhs, ArithmeticMode::Checked)?;
+        (lhs, rhs) = (rhs, rem);
+    }
+    Ok(lhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_functions() {
+        let functions = Functions::builtin();
+        let options = EvalOptions::new();
+        let call = |name, args: &[i128]| functions.get(name).unwrap().call(args, &options);
+
+        assert_eq!(call("max", &[3, -1, 7]), Ok(7));
+        assert_eq!(call("min", &[3, -1, 7]), Ok(-1));
+        assert_eq!(call("abs", &[-5]), Ok(5));
+        assert_eq!(call("abs", &[i128::MIN]), Err(ErrorKind::Overflow));
+        assert_eq!(call("gcd", &[12, -18]), Ok(6));
+        assert_eq!(call("gcd", &[0, 0]), Ok(0));
+        assert_eq!(call("gcd", &[i128::MIN, 0]), Err(ErrorKind::Overflow));
+
+        // abs follows the arithmetic mode
+        let wrapping = EvalOptions::new().arithmetic(ArithmeticMode::Wrapping);
+        let saturating = EvalOptions::new().arithmetic(ArithmeticMode::Saturating);
+        let abs = functions.get("abs").unwrap();
+        assert_eq!(abs.call(&[i128::MIN], &wrapping), Ok(i128::MIN));
+        assert_eq!(abs.call(&[i128::MIN], &saturating), Ok(i128::MAX));
+        assert_eq!(abs.call(&[-5], &saturating), Ok(5));
+    }
+
+    #[test]
+    fn test_pow() {
+        let pow = |base: i128, exponent: i128| pow(&base, &exponent, &EvalOptions::new());
+        assert_eq!(pow(2, 10), Ok(1024));
+        assert_eq!(pow(-3, 3), Ok(-27));
+        assert_eq!(pow(7, 0), Ok(1));
+        assert_eq!(pow(2, 127), Err(ErrorKind::Overflow));
+        assert_eq!(pow(2, -1), Ok(0));
+        assert_eq!(pow(-1, -3), Ok(-1));
+        assert_eq!(pow(0, -1), Err(ErrorKind::DivisionByZero));
+        assert_eq!(pow(-1, i128::MAX), Ok(-1));
+        assert_eq!(pow(2, i128::MAX), Err(ErrorKind::Overflow));
+    }
+
+    #[test]
+    fn arity() {
+        assert!(Arity::Exact(2).accepts(2));
+        assert!(!Arity::Exact(2).accepts(3));
+        assert!(Arity::AtLeast(1).accepts(5));
+        assert!(!Arity::AtLeast(1).accepts(0));
+        assert_eq!(Arity::AtLeast(1).to_string(), "at least 1 argument");
+        assert_eq!(Arity::Exact(2).to_string(), "2 arguments");
+    }
+}
@@ -0,0 +1,655 @@
+//! Turn an expression into a stream of [`Token`]s
+//!
+//! Example:
+//! ```
+//! use parser_rs::{Lexer, Operator, Span, Syntax, Token, TokenKind};
+//!
+//! let tokens = Lexer::tokenize("12 c e3", &Syntax::letters()).unwrap();
+//! assert_eq!(
+//!     tokens,
+//!     vec![
+//!         Token::new(TokenKind::Number(12), Span::new(0, 2)),
+//!         Token::new(TokenKind::Op(Operator::Mul), Span::new(3, 4)),
+//!         Token::new(TokenKind::LParen, Span::new(5, 6)),
+//!         Token::new(TokenKind::Number(3), Span::new(6, 7)),
+//!         Token::new(TokenKind::End, Span::empty(7)),
+//!     ]
+//! );
+//! ```
+
+use crate::syntax::is_word_char;
+use crate::{ErrorKind, EvalError, Number, Rounding, Span, Symbol, Syntax};
+use std::marker::PhantomData;
+
+pub(crate) const RADIX: u32 = 10;
+
+/// Binary arithmetic operator
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    /// Division rounding toward zero
+    Div,
+    /// Remainder of `Div`, has the sign of the left side
+    Rem,
+    /// Euclidean modulo, never negative
+    Mod,
+    /// Exponentiation
+    Pow,
+    /// Division rounding toward negative infinity
+    FloorDiv,
+    BitAnd,
+    BitOr,
+    BitXor,
+    /// Shift left, multiplying by a power of 2
+    Shl,
+    /// Arithmetic shift right, dividing by a power of 2 rounding down
+    Shr,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    /// Logical and, right side is only computed if left side is true
+    And,
+    /// Logical or, right side is only computed if left side is false
+    Or,
+}
+
+/// Operator applied to the single operand after it
+/// Sign is an `Operator` instead, since it is written the same as a binary operator
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum UnaryOperator {
+    /// Flip every bit
+    BitNot,
+    /// Logical negation
+    Not,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TokenKind<N = i128> {
+    Number(N),
+    /// Name of a variable or function
+    Ident(String),
+    Op(Operator),
+    Unary(UnaryOperator),
+    LParen,
+    RParen,
+    /// Separator between statements
+    EndStatement,
+    Assign,
+    ArgSeparator,
+    Then,
+    Else,
+    /// End of the expression. Always the last token
+    End,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token<N = i128> {
+    pub kind: TokenKind<N>,
+    pub span: Span,
+}
+
+impl<N> Token<N> {
+    pub fn new(kind: TokenKind<N>, span: Span) -> Self {
+        Token { kind, span }
+    }
+}
+
+/// Iterator over tokens of an expression
+///
+/// Last item is either a `TokenKind::End` token or the first error found
+pub struct Lexer<'a, N = i128> {
+    source: &'a str,
+    syntax: &'a Syntax,
+    /// Radix of literals without a prefix
+    radix: u32,
+    /// Rounding of literal digits the number type does not keep
+    rounding: Rounding,
+    /// Byte offset of the next token
+    offset: usize,
+    finished: bool,
+    number: PhantomData<N>,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str, syntax: &'a Syntax) -> Self {
+        Lexer::with_backend(source, syntax)
+    }
+
+    /// Collect every token of `source`, including the final `TokenKind::End`
+    pub fn tokenize(source: &'a str, syntax: &'a Syntax) -> Result<Vec<Token>, EvalError> {
+        Lexer::new(source, syntax).collect()
+    }
+}
+
+impl<'a, N: Number> Lexer<'a, N> {
+    /// Lexer reading literals as numbers of type `N`
+    pub fn with_backend(source: &'a str, syntax: &'a Syntax) -> Self {
+        Lexer {
+            source,
+            syntax,
+            radix: RADIX,
+            rounding: Rounding::default(),
+            offset: 0,
+            finished: false,
+            number: PhantomData,
+        }
+    }
+
+    /// Read literals without a prefix in `radix` instead of 10
+    ///
+    /// Panics if `radix` is not in `2..=36`
+    pub fn radix(mut self, radix: u32) -> Self {
+        assert!((2..=36).contains(&radix), "Invalid radix: {radix}");
+        self.radix = radix;
+        self
+    }
+
+    /// Round literal digits the number type does not keep by `rounding`
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    fn next_token(&mut self) -> Result<Token<N>, EvalError> {
+        let rest = self.source[self.offset..].trim_start();
+        self.offset = self.source.len() - rest.len();
+
+        let Some(ch) = rest.chars().next() else {
+            return Ok(Token::new(TokenKind::End, Span::empty(self.offset)));
+        };
+        if ch.is_ascii_digit() {
+            return self.number(rest);
+        }
+        if let Some(len) = self.syntax.match_name(rest) {
+            return Ok(self.token(TokenKind::Ident(rest[..len].to_string()), len));
+        }
+
+        let Some((symbol, len)) = self.syntax.match_symbol(rest) else {
+            if ch.is_alphabetic() || ch == '_' {
+                return Ok(self.identifier(rest));
+            }
+            return Err(EvalError::new(
+                ErrorKind::UnexpectedCharacter(ch),
+                Span::of_char(self.offset, ch),
+            ));
+        };
+        let kind = match symbol {
+            Symbol::Op(operator) => TokenKind::Op(operator),
+            Symbol::Unary(operator) => TokenKind::Unary(operator),
+            Symbol::LParen => TokenKind::LParen,
+            Symbol::RParen => TokenKind::RParen,
+            Symbol::EndStatement => TokenKind::EndStatement,
+            Symbol::Assign => TokenKind::Assign,
+            Symbol::ArgSeparator => TokenKind::ArgSeparator,
+            Symbol::Then => TokenKind::Then,
+            Symbol::Else => TokenKind::Else,
+        };
+        Ok(self.token(kind, len))
+    }
+
+    /// Skip rest of the current statement, up to and including
+    /// the next `Symbol::EndStatement`, and resume lexing after it.
+    /// Used to recover from an error in the middle of a statement
+    pub fn skip_statement(&mut self) {
+        self.finished = false;
+        while let Some(ch) = self.source[self.offset..].chars().next() {
+            if let Some((Symbol::EndStatement, len)) =
+                self.syntax.match_symbol(&self.source[self.offset..])
+            {
+                self.offset += len;
+                return;
+            }
+            self.offset += ch.len_utf8();
+        }
+    }
+
+    /// Read the variable name at the start of `rest`
+    /// Name ends before the first symbol that is not a word, like `a` of letter syntax
+    fn identifier(&mut self, rest: &str) -> Token<N> {
+        let len = rest
+            .char_indices()
+            .find(|&(offset, ch)| {
+                offset > 0 && (!is_word_char(ch) || self.syntax.breaks_word(&rest[offset..]))
+            })
+            .map_or(rest.len(), |(offset, _)| offset);
+        self.token(TokenKind::Ident(rest[..len].to_string()), len)
+    }
+
+    /// Read the number literal at the start of `rest`
+    ///
+    /// `0x`, `0o` and `0b` prefixes, of either case, pick radix 16, 8 and 2.
+    /// Digits may be separated by `_`, like `1_000`.
+    /// A letter starting a symbol of the syntax is never a digit or a prefix,
+    /// so in letter syntax `0x1fa2` is `0x1) + 2` and hex digits are written `0x1FA2`,
+    /// while `0b1` is `0 - 1` and binary is written `0B1`.
+    /// In a default radix above 11 `0b` is not a prefix, so `0B11` in radix 16 is `0xB11`.
+    ///
+    /// Fraction and exponent are only read if the number type has them and the radix is 10,
+    /// and only when digits follow, so `2e3` is `2000`
+    /// while `2e` still opens a parenthesis in letter syntax
+    fn number(&mut self, rest: &str) -> Result<Token<N>, EvalError> {
+        let (radix, prefix) = self.prefix(rest);
+        let mut len = prefix + self.digits(&rest[prefix..], radix);
+        // digit from 0 to 9 that is not a digit of the radix, like `9` in radix 2
+        if len == 0 {
+            let ch = rest.chars().next().unwrap_or_default();
+            return Err(EvalError::new(
+                ErrorKind::UnexpectedCharacter(ch),
+                Span::of_char(self.offset, ch),
+            ));
+        }
+        if N::FRACTIONAL && radix == RADIX {
+            if let Some(fraction) = rest[len..].strip_prefix('.') {
+                if self.digits(fraction, RADIX) > 0 {
+                    len += 1 + self.digits(fraction, RADIX);
+                }
+            }
+            if let Some(exponent) = rest[len..].strip_prefix(['e', 'E']) {
+                let sign = usize::from(exponent.starts_with(['+', '-']));
+                if self.digits(&exponent[sign..], RADIX) > 0 {
+                    len += 1 + sign + self.digits(&exponent[sign..], RADIX);
+                }
+            }
+        }
+
+        let span = Span::new(self.offset, self.offset + len);
+        let literal = rest[prefix..len].replace('_', "");
+        let number = match radix {
+            RADIX => N::from_literal(&literal, self.rounding),
+            _ => N::from_digits(&literal, radix),
+        };
+        let number = number.ok_or(EvalError::new(ErrorKind::LiteralOverflow, span))?;
+        Ok(self.token(TokenKind::Number(number), len))
+    }
+
+    /// Radix of the literal at the start of `rest` and length of its prefix.
+    /// A prefix counts only if a digit follows it
+    /// and its letter is not a digit of the default radix
+    fn prefix(&self, rest: &str) -> (u32, usize) {
+        let radix = match rest.as_bytes() {
+            [b'0', b'x' | b'X', ..] => 16,
+            [b'0', b'o' | b'O', ..] => 8,
+            [b'0', b'b' | b'B', ..] => 2,
+            _ => return (self.radix, 0),
+        };
+        let letter = char::from(rest.as_bytes()[1]);
+        if letter.is_digit(self.radix) {
+            return (self.radix, 0);
+        }
+        match !self.syntax.breaks_word(&rest[1..]) && self.digits(&rest[2..], radix) > 0 {
+            true => (radix, 2),
+            false => (self.radix, 0),
+        }
+    }
+
+    /// Length of the run of digits in `radix` at the start of `text`,
+    /// with single `_` allowed between digits
+    fn digits(&self, text: &str, radix: u32) -> usize {
+        let is_digit = |offset: usize| {
+            text[offset..]
+                .chars()
+                .next()
+                .is_some_and(|ch| ch.is_digit(radix))
+                && !self.syntax.breaks_word(&text[offset..])
+        };
+        let mut len = 0;
+        while is_digit(len) {
+            len += 1;
+            if text[len..].starts_with('_') && is_digit(len + 1) {
+                len += 1;
+            }
+        }
+        len
+    }
+
+    /// Token of `kind` covering next `len` bytes
+    fn token(&mut self, kind: TokenKind<N>, len: usize) -> Token<N> {
+        let span = Span::new(self.offset, self.offset + len);
+        self.offset += len;
+        Token::new(kind, span)
+    }
+}
+
+impl<N: Number> Iterator for Lexer<'_, N> {
+    type Item = Result<Token<N>, EvalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let token = self.next_token();
+        self.finished = match &token {
+            Ok(token) => token.kind == TokenKind::End,
+            Err(_) => true,
+        };
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Decimal;
+
+    fn kinds(source: &str) -> Result<Vec<TokenKind>, EvalError> {
+        Lexer::new(source, &Syntax::letters())
+            .map(|token| token.map(|token| token.kind))
+            .collect()
+    }
+
+    #[test]
+    fn token_kinds() {
+        assert_eq!(kinds(""), Ok(vec![TokenKind::End]));
+        assert_eq!(
+            kinds(" 10 a e 5 d 2 f "),
+            Ok(vec![
+                TokenKind::Number(10),
+                TokenKind::Op(Operator::Add),
+                TokenKind::LParen,
+                TokenKind::Number(5),
+                TokenKind::Op(Operator::Div),
+                TokenKind::Number(2),
+                TokenKind::RParen,
+                TokenKind::End,
+            ])
+        );
+    }
+
+    #[test]
+    fn token_spans() {
+        let spans = Lexer::new("123b 4", &Syntax::letters())
+            .map(|token| token.unwrap().span)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                Span::new(0, 3),
+                Span::new(3, 4),
+                Span::new(5, 6),
+                Span::empty(6),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        let syntax = Syntax::letters();
+        let mut lexer = Lexer::new("1 @ 2", &syntax);
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(TokenKind::Number(1), Span::new(0, 1))))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(EvalError::new(
+                ErrorKind::UnexpectedCharacter('@'),
+                Span::new(2, 3)
+            )))
+        );
+        assert_eq!(lexer.next(), None);
+
+        // lexing can continue with the next statement
+        lexer.skip_statement();
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(TokenKind::End, Span::empty(5))))
+        );
+
+        let mut lexer = Lexer::new("1 @ 2; 3", &syntax);
+        assert!(lexer.nth(1).unwrap().is_err());
+        lexer.skip_statement();
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(Token::new(TokenKind::Number(3), Span::new(7, 8))))
+        );
+    }
+
+    #[test]
+    fn multi_char_symbols() {
+        let syntax = Syntax::standard()
+            .with_symbol("div", Symbol::Op(Operator::Div))
+            .with_symbol("**", Symbol::Op(Operator::Mul));
+        let kinds = Lexer::new("(8div2)**3", &syntax)
+            .map(|token| token.unwrap().kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::LParen,
+                TokenKind::Number(8),
+                TokenKind::Op(Operator::Div),
+                TokenKind::Number(2),
+                TokenKind::RParen,
+                TokenKind::Op(Operator::Mul),
+                TokenKind::Number(3),
+                TokenKind::End,
+            ]
+        );
+
+        // letters are variable names in standard syntax
+        assert_eq!(
+            Lexer::tokenize("1 a 2", &Syntax::standard()).map(|tokens| tokens[1].kind.clone()),
+            Ok(TokenKind::Ident("a".to_string()))
+        );
+    }
+
+    #[test]
+    fn identifiers() {
+        let ident = |name: &str| TokenKind::Ident(name.to_string());
+
+        // letter operators end a name
+        assert_eq!(
+            kinds("x1 = 3a2; x1cy_z"),
+            Ok(vec![
+                ident("x1"),
+                TokenKind::Assign,
+                TokenKind::Number(3),
+                TokenKind::Op(Operator::Add),
+                TokenKind::Number(2),
+                TokenKind::EndStatement,
+                ident("x1"),
+                TokenKind::Op(Operator::Mul),
+                ident("y_z"),
+                TokenKind::End,
+            ])
+        );
+
+        // word symbols only match whole words
+        let syntax = Syntax::standard().with_symbol("div", Symbol::Op(Operator::Div));
+        let kinds = Lexer::new("divisor div xdiv", &syntax)
+            .map(|token| token.unwrap().kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ident("divisor"),
+                TokenKind::Op(Operator::Div),
+                ident("xdiv"),
+                TokenKind::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn reserved_names() {
+        let syntax = Syntax::letters().with_name("max");
+        let kinds = Lexer::new("max e1, 2f; xmax", &syntax)
+            .map(|token| token.unwrap().kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Ident("max".to_string()),
+                TokenKind::LParen,
+                TokenKind::Number(1),
+                TokenKind::ArgSeparator,
+                TokenKind::Number(2),
+                TokenKind::RParen,
+                TokenKind::EndStatement,
+                // only a whole word is the reserved name
+                TokenKind::Ident("xm".to_string()),
+                TokenKind::Op(Operator::Add),
+                TokenKind::Ident("x".to_string()),
+                TokenKind::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn fractional_literals() {
+        let kinds = |source| {
+            Lexer::<f64>::with_backend(source, &Syntax::letters())
+                .map(|token| token.map(|token| token.kind))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        assert_eq!(
+            kinds("1.5e3a2E-1"),
+            Ok(vec![
+                TokenKind::Number(1500.0),
+                TokenKind::Op(Operator::Add),
+                TokenKind::Number(0.2),
+                TokenKind::End,
+            ])
+        );
+        // without digits after them `e` and `.` are not part of the number
+        assert_eq!(
+            kinds("3ee1f"),
+            Ok(vec![
+                TokenKind::Number(3.0),
+                TokenKind::LParen,
+                TokenKind::LParen,
+                TokenKind::Number(1.0),
+                TokenKind::RParen,
+                TokenKind::End,
+            ])
+        );
+        assert_eq!(
+            kinds("1."),
+            Err(EvalError::new(
+                ErrorKind::UnexpectedCharacter('.'),
+                Span::new(1, 2)
+            ))
+        );
+
+        // integers have no fraction
+        assert_eq!(
+            Lexer::tokenize("3.5", &Syntax::letters()).map_err(|e| e.kind),
+            Err(ErrorKind::UnexpectedCharacter('.'))
+        );
+
+        // digits the number type does not keep are rounded
+        let cents = |source, rounding| {
+            Lexer::<Decimal>::with_backend(source, &Syntax::letters())
+                .rounding(rounding)
+                .map(|token| token.map(|token| token.kind))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        assert_eq!(
+            cents("1.005", Rounding::Ceiling),
+            Ok(vec![
+                TokenKind::Number(Decimal::from_units(101)),
+                TokenKind::End
+            ])
+        );
+        assert_eq!(
+            cents("1.005", Rounding::HalfEven),
+            Ok(vec![
+                TokenKind::Number(Decimal::from_units(100)),
+                TokenKind::End
+            ])
+        );
+    }
+
+    #[test]
+    fn radix_literals() {
+        let number = |value| TokenKind::Number(value);
+        assert_eq!(
+            kinds("0x1F a 0o17 c 0B1010 a 1_000_000"),
+            Ok(vec![
+                number(31),
+                TokenKind::Op(Operator::Add),
+                number(15),
+                TokenKind::Op(Operator::Mul),
+                number(10),
+                TokenKind::Op(Operator::Add),
+                number(1_000_000),
+                TokenKind::End,
+            ])
+        );
+        // letters of letter syntax are operators, not digits or prefixes
+        assert_eq!(
+            kinds("0x1fa2 b 0b1"),
+            Ok(vec![
+                number(1),
+                TokenKind::RParen,
+                TokenKind::Op(Operator::Add),
+                number(2),
+                TokenKind::Op(Operator::Sub),
+                number(0),
+                TokenKind::Op(Operator::Sub),
+                number(1),
+                TokenKind::End,
+            ])
+        );
+        // prefix or separator without a digit after it is not part of the number
+        assert_eq!(
+            kinds("0xg 1__0 2_"),
+            Ok(vec![
+                number(0),
+                TokenKind::Ident("x".to_string()),
+                TokenKind::Op(Operator::Rem),
+                number(1),
+                TokenKind::Ident("__0".to_string()),
+                number(2),
+                TokenKind::Ident("_".to_string()),
+                TokenKind::End,
+            ])
+        );
+
+        let hex = |source| {
+            Lexer::new(source, &Syntax::standard())
+                .radix(16)
+                .map(|token| token.map(|token| token.kind))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        assert_eq!(
+            hex("0ff + FF - 0b11 - 0x11"),
+            Ok(vec![
+                number(255),
+                TokenKind::Op(Operator::Add),
+                TokenKind::Ident("FF".to_string()),
+                TokenKind::Op(Operator::Sub),
+                number(0xb11),
+                TokenKind::Op(Operator::Sub),
+                number(0x11),
+                TokenKind::End,
+            ])
+        );
+        assert_eq!(
+            hex("0x1_0000_0000_0000_0000_0000_0000_0000_0000").map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::LiteralOverflow, Span::new(0, 43)))
+        );
+
+        // literal must start with a digit of the radix
+        let binary = |source| {
+            Lexer::new(source, &Syntax::standard())
+                .radix(2)
+                .collect::<Result<Vec<Token>, _>>()
+                .map_err(|e| (e.kind, e.span))
+        };
+        assert_eq!(
+            binary("10 + 9"),
+            Err((ErrorKind::UnexpectedCharacter('9'), Span::new(5, 6)))
+        );
+        assert_eq!(
+            binary("12"),
+            Err((ErrorKind::UnexpectedCharacter('2'), Span::new(1, 2)))
+        );
+    }
+}
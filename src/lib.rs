@@ -0,0 +1,882 @@
+//! # Parser-rs
+//! build up a numeric result from given expression
+//!
+//! Allowed expression are numeric values, operators and parenthesis.
+//! Unless in parenthesis, computation occuer from left to right.
+//! Conventional operator precedence can be opted in with [`Strategy::Precedence`]
+//!
+//! Allowed operators are: +, -, *, /
+//! represented by a,b,c,d respectively.
+//! Remainder, Euclidean modulo, exponent and floor division
+//! are represented by g,h,i,j respectively
+//! Likewise, Open and close parenthesis are represented by e, f respectively.
+//! Conventional symbols or any other can be used instead with a [`Syntax`]
+//!
+//! Number literals may be written in hex, octal or binary like `0x1F`, `0o17` or `0B1010`,
+//! and digits may be grouped with `_` like `1_000_000`.
+//! Default radix can be changed with [`EvalOptions::radix`]
+//!
+//! Statements are separated by `;` and values can be kept in variables with `=`.
+//! Variables live in an [`Environment`], which may be reused across evaluations
+//!
+//! Comparisons and logical operators produce bools, as in `x > 10 && y <= 3`,
+//! which pick between values with `condition ? value : other`.
+//! See [`Evaluator::evaluate_value_in`] to get such results.
+//!
+//! Functions are called like `max e3, 7f`. Built-in ones are `max`, `min`, `abs`,
+//! `pow` and `gcd`; more can be registered with [`Evaluator::with_function`]
+//!
+//! Numbers are `i128` by default. Any other [`Number`] type, like `i64`, `u64`, `f64`,
+//! exact fractions of [`Rational`], unbounded [`BigInt`] or fixed-point [`Decimal`],
+//! can be picked with [`Evaluator::with_backend`].
+//! Results that need rounding are rounded as set by [`EvalOptions::rounding`].
+//! A [`NumberFormat`] writes results in hex, with grouped digits, in scientific notation
+//! or as a fraction
+//!
+//! Example:
+//! ```
+//! use parser_rs::{compute, Evaluator};
+//!
+//! // 3 + 2 * 4 = 5*4 = 20
+//! assert_eq!(compute("3a2c4"), Ok(20));
+//! assert_eq!(Evaluator::new().evaluate("3ae4c66fb32"), Ok(235));
+//! ```
+
+mod ast;
+mod big_int;
+mod decimal;
+mod diagnostic;
+mod env;
+mod error;
+mod eval;
+mod format;
+mod functions;
+mod lexer;
+mod number;
+mod options;
+mod parser;
+mod rational;
+mod span;
+mod syntax;
+mod value;
+
+pub use ast::{Expr, ExprDisplay};
+pub use big_int::BigInt;
+pub use decimal::Decimal;
+pub use diagnostic::Diagnostic;
+pub use env::Environment;
+pub use error::{ErrorKind, EvalError};
+pub use eval::Evaluator;
+pub use format::{Notation, NumberFormat};
+pub use functions::Arity;
+pub use lexer::{Lexer, Operator, Token, TokenKind, UnaryOperator};
+pub use number::{Integer, Number};
+pub use options::{ArithmeticMode, EvalOptions, Rounding, Strategy};
+pub use parser::Parser;
+pub use rational::Rational;
+pub use span::Span;
+pub use syntax::{Symbol, Syntax};
+pub use value::{Type, Value};
+
+/// Compute the numeric result of given expression
+/// with the default evaluator
+pub fn compute(raw_expression: &str) -> Result<i128, EvalError> {
+    Evaluator::new().evaluate(raw_expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NOTE:
+    // this is the test given in assigment file
+    #[test]
+    fn assignment_test() {
+        // 3 + 2 * 4 = 5*4 = 20
+        assert_eq!(compute("3a2c4"), Ok(20));
+        // 32 + 2 / 2 = 34/2 = 17
+        assert_eq!(compute("32a2d2"), Ok(17));
+        // 500 + 10 - 66 * 32 = 510-66*32 = 444*32 = 14208
+        assert_eq!(compute("500a10b66c32"), Ok(14208));
+        // 3 + (4 * 66) - 32 = 3+264-32 = 267-32 = 235
+        assert_eq!(compute("3ae4c66fb32"), Ok(235));
+
+        // 3 * 4 / 2 + ((2 + 4 * 41) * 4)
+        // = 12/2+((2+4*41)*4) = 6+(246*4)
+        // = 6+984 = 990
+        assert_eq!(compute("3c4d2aee2a4c41fc4f"), Ok(990));
+    }
+
+    #[test]
+    fn empty_string() {
+        assert_eq!(compute(""), Ok(0));
+    }
+
+    #[test]
+    fn single_expression() {
+        assert_eq!(compute("9"), Ok(9));
+        assert_eq!(compute(" 0 "), Ok(0));
+    }
+
+    #[test]
+    fn two_expression() {
+        // 9 - 9 = 0
+        assert_eq!(compute("9 b 9"), Ok(0));
+        // 9 + 9 = 18
+        assert_eq!(compute("9 a 9"), Ok(18));
+        // 5 * 4 = 20
+        assert_eq!(compute("5 c 4"), Ok(20));
+        // 100 / 10 10
+        assert_eq!(compute("100 d 10"), Ok(10));
+    }
+
+    #[test]
+    fn multi_expression() {
+        // 9 - 9 * 10 = 0 * 10 = 0
+        assert_eq!(compute("9 b 9 c 10"), Ok(0));
+        // 10 + 10 - 10 * 10 / 10 = 20-10*10/10 = 10*10/10 = 100/10 = 10
+        assert_eq!(compute("10 a 10 b 10 c 10 d 10"), Ok(10));
+    }
+
+    #[test]
+    fn can_start_with_operator() {
+        // - 10 + 50 = 0 - 10 + 50 = -10 + 50 = 40
+        assert_eq!(compute("b 10 a 50"), Ok(40));
+    }
+
+    #[test]
+    fn parenthesis_emphasize() {
+        // 10 + 5 * 3 - 1 = 15*3-1 = 45-1 = 44
+        assert_eq!(compute("10 a 5 c 3 b 1"), Ok(44));
+        // 10 + (5*3) - 1 = 10+15-1 = 25-1 = 24
+        assert_eq!(compute("10 a e 5 c 3 b 1 f"), Ok(24));
+        // 10 + ( 5 * (3 - 1) ) - (10 - 5) + 5 = 10+(5*2)-(10-5)+5 = 10+10-(10-5)+5
+        // = 10+10-5+5 = 20-5+5 = 15+5 = 20
+        assert_eq!(compute("10 a e5 c e3 b 1 ff b e 10 b 5f a 5"), Ok(20));
+    }
+
+    #[test]
+    fn negative_parenthesis() {
+        // 3 + (1 - 5) = 3+-4 = -1
+        assert_eq!(compute("3ae1b5f"), Ok(-1));
+        // (1 - 5) * 2 = -4*2 = -8
+        assert_eq!(compute("e1b5fc2"), Ok(-8));
+        // 2 * ((0 - 7) * 3) = 2*(-7*3) = 2*-21 = -42
+        assert_eq!(compute("2 c e e b 7 f c 3 f"), Ok(-42));
+    }
+
+    #[test]
+    fn unary_signs() {
+        // 3 * -2 = -6
+        assert_eq!(compute("3 c b2"), Ok(-6));
+        // (-5) = -5
+        assert_eq!(compute("e b5 f"), Ok(-5));
+        // --5 = 5, -+-5 = 5
+        assert_eq!(compute("bb5"), Ok(5));
+        assert_eq!(compute("b a b 5"), Ok(5));
+        // 10 - -(2 + 3) = 15
+        assert_eq!(compute("10 b b e2 a 3f"), Ok(15));
+        // 1 - -1 * -1 = 2 * -1 = -2
+        assert_eq!(compute("1 b b1 c b1"), Ok(-2));
+
+        // sign binds tighter than any operator but exponent in both strategies
+        let precedence = Evaluator::with_options(EvalOptions::new().strategy(Strategy::Precedence));
+        // 2 + (-3 * 4) = -10
+        assert_eq!(precedence.evaluate("2 a b3 c 4"), Ok(-10));
+        // (-2) * 3 - -1 = -5
+        assert_eq!(precedence.evaluate("b2 c 3 b b1"), Ok(-5));
+        // (2 + -3) * 4 = -4
+        assert_eq!(compute("2 a b3 c 4"), Ok(-4));
+
+        let error = |expression| compute(expression).map_err(|e| (e.kind, e.offset()));
+        assert_eq!(error("3 c b"), Err((ErrorKind::MissingOperand, 5)));
+        assert_eq!(error("3 c b c 2"), Err((ErrorKind::MissingOperand, 6)));
+        // -(-2^127) does not fit
+        assert_eq!(
+            error("b e b 170141183460469231731687303715884105727 b 1 f"),
+            Err((ErrorKind::Overflow, 0))
+        );
+    }
+
+    #[test]
+    fn error_offsets() {
+        let error = |expression| compute(expression).map_err(|e| (e.kind, e.offset()));
+
+        assert_eq!(
+            error("3 a @"),
+            Err((ErrorKind::UnexpectedCharacter('@'), 4))
+        );
+        assert_eq!(
+            error("3 a e 2 c e 1"),
+            Err((ErrorKind::UnclosedParenthesis, 4))
+        );
+        assert_eq!(error("3 a 2 f"), Err((ErrorKind::UnmatchedParenthesis, 6)));
+        assert_eq!(error("3 a c 2"), Err((ErrorKind::MissingOperand, 4)));
+        assert_eq!(error("3 a"), Err((ErrorKind::MissingOperand, 3)));
+        assert_eq!(error("3 e 2 f"), Err((ErrorKind::MissingOperator, 2)));
+        assert_eq!(error("e 2 f 3"), Err((ErrorKind::MissingOperator, 6)));
+        // offset inside parenthesis is relative to whole expression
+        assert_eq!(error("1 a e 4 d 0 f"), Err((ErrorKind::DivisionByZero, 8)));
+        assert_eq!(
+            error("1000000000 c 1000000000 c 1000000000 c 1000000000 c 1000000000"),
+            Err((ErrorKind::Overflow, 50))
+        );
+    }
+
+    #[test]
+    fn error_spans() {
+        // operator span covers the operator character
+        assert_eq!(compute("4 d 0").unwrap_err().span, Span::new(2, 3));
+        // end of input has nothing to cover
+        assert_eq!(compute("4 d").unwrap_err().span, Span::empty(3));
+    }
+
+    #[test]
+    fn arithmetic_modes() {
+        // (2^127 - 1) + 1
+        let expression = "170141183460469231731687303715884105727 a 1";
+        let evaluate = |mode| {
+            Evaluator::with_options(EvalOptions::new().arithmetic(mode)).evaluate(expression)
+        };
+
+        assert_eq!(
+            evaluate(ArithmeticMode::Checked).map_err(|e| e.kind),
+            Err(ErrorKind::Overflow)
+        );
+        assert_eq!(evaluate(ArithmeticMode::Wrapping), Ok(i128::MIN));
+        assert_eq!(evaluate(ArithmeticMode::Saturating), Ok(i128::MAX));
+
+        // division by zero has no meaningful value in any mode
+        let evaluator =
+            Evaluator::with_options(EvalOptions::new().arithmetic(ArithmeticMode::Saturating));
+        assert_eq!(
+            evaluator.evaluate("1 d 0").map_err(|e| e.kind),
+            Err(ErrorKind::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn precedence_strategy() {
+        let evaluator = Evaluator::with_options(EvalOptions::new().strategy(Strategy::Precedence));
+        let compute = |expression| evaluator.evaluate(expression);
+
+        // 3 + 2 * 4 = 3+8 = 11
+        assert_eq!(compute("3a2c4"), Ok(11));
+        // 32 + 2 / 2 = 32+1 = 33
+        assert_eq!(compute("32a2d2"), Ok(33));
+        // 500 + 10 - 66 * 32 = 510-2112 = -1602
+        assert_eq!(compute("500a10b66c32"), Ok(-1602));
+        // 3 * 4 / 2 + ((2 + 4 * 41) * 4) = 6+(166*4) = 6+664 = 670
+        assert_eq!(compute("3c4d2aee2a4c41fc4f"), Ok(670));
+        // 1 + 2 * 3 * 4 - 5 = 1+24-5 = 20
+        assert_eq!(compute("1 a 2 c 3 c 4 b 5"), Ok(20));
+        // same precedence still goes left to right
+        // 10 - 4 - 3 = 3, 64 / 4 / 2 = 8
+        assert_eq!(compute("10 b 4 b 3"), Ok(3));
+        assert_eq!(compute("64 d 4 d 2"), Ok(8));
+        // 1 - 6 / 0
+        assert_eq!(
+            compute("1 b 6 d 0").map_err(|e| (e.kind, e.offset())),
+            Err((ErrorKind::DivisionByZero, 6))
+        );
+    }
+
+    #[test]
+    fn more_operators() {
+        // 17 % 5 = 2, -17 % 5 = -2, -17 mod 5 = 3
+        assert_eq!(compute("17 g 5"), Ok(2));
+        assert_eq!(compute("b17 g 5"), Ok(-2));
+        assert_eq!(compute("b17 h 5"), Ok(3));
+        assert_eq!(compute("b17 h b5"), Ok(3));
+        // -7 / 2 = -3, -7 // 2 = -4
+        assert_eq!(compute("b7 d 2"), Ok(-3));
+        assert_eq!(compute("b7 j 2"), Ok(-4));
+        // (2 + 1) ** 2 ** 2 = 9**2 = 81 left to right
+        assert_eq!(compute("2 a 1 i 2 i 2"), Ok(81));
+        // (-2) ** 2 = 4
+        assert_eq!(compute("b2 i 2"), Ok(4));
+
+        let precedence = Evaluator::with_options(EvalOptions::new().strategy(Strategy::Precedence));
+        // 2 ** (3 ** 2) = 512
+        assert_eq!(precedence.evaluate("2 i 3 i 2"), Ok(512));
+        // 1 + 2 * 3 ** 2 = 19
+        assert_eq!(precedence.evaluate("1 a 2 c 3 i 2"), Ok(19));
+        // -(2 ** 2) = -4, 2 ** -1 = 0
+        assert_eq!(precedence.evaluate("b2 i 2"), Ok(-4));
+        assert_eq!(precedence.evaluate("2 i b1"), Ok(0));
+        // 1 + 7 % 4 * 2 = 1 + 3*2 = 7
+        assert_eq!(precedence.evaluate("1 a 7 g 4 c 2"), Ok(7));
+
+        let standard = Evaluator::with_options(
+            EvalOptions::new()
+                .syntax(Syntax::standard())
+                .strategy(Strategy::Precedence),
+        );
+        assert_eq!(
+            standard.evaluate_all("-7 % 3; -7 mod 3; -7 // 2; 2 ** 10"),
+            vec![Ok(-1), Ok(2), Ok(-4), Ok(1024)]
+        );
+
+        let error = |expression| compute(expression).map_err(|e| (e.kind, e.offset()));
+        assert_eq!(error("1 g 0"), Err((ErrorKind::DivisionByZero, 2)));
+        assert_eq!(error("1 h 0"), Err((ErrorKind::DivisionByZero, 2)));
+        assert_eq!(error("1 j 0"), Err((ErrorKind::DivisionByZero, 2)));
+        assert_eq!(error("0 i b1"), Err((ErrorKind::DivisionByZero, 2)));
+        assert_eq!(error("2 i 127"), Err((ErrorKind::Overflow, 2)));
+        let wrapping =
+            Evaluator::with_options(EvalOptions::new().arithmetic(ArithmeticMode::Wrapping));
+        assert_eq!(wrapping.evaluate("2 i 127 g b1"), Ok(0));
+    }
+
+    #[test]
+    fn bitwise_operators() {
+        let options = EvalOptions::new().syntax(Syntax::standard().with_bitwise());
+        let left_to_right = Evaluator::with_options(options.clone());
+        let precedence = Evaluator::with_options(options.strategy(Strategy::Precedence));
+
+        assert_eq!(
+            left_to_right.evaluate_all("12 & 10; 12 | 3; 12 ^ 10; ~0; ~~5; 1 << 4; -16 >> 2"),
+            vec![Ok(8), Ok(15), Ok(6), Ok(-1), Ok(5), Ok(16), Ok(-4)]
+        );
+        // (1 + 2) << 3 = 24 in both strategies
+        assert_eq!(left_to_right.evaluate("1 + 2 << 3"), Ok(24));
+        assert_eq!(precedence.evaluate("1 + 2 << 3"), Ok(24));
+        // 1 | ((6 & 3) ^ 5) = 1 | (2 ^ 5) = 7
+        assert_eq!(precedence.evaluate("1 | 6 & 3 ^ 5"), Ok(7));
+        // ((1 | 6) & 3) ^ 5 = (7 & 3) ^ 5 = 6
+        assert_eq!(left_to_right.evaluate("1 | 6 & 3 ^ 5"), Ok(6));
+        // (~1) * 2 = -4, ~(2 ** 2) = -5
+        assert_eq!(precedence.evaluate("~1 * 2"), Ok(-4));
+        assert_eq!(precedence.evaluate("~2 ** 2"), Ok(-5));
+
+        let error = |expression| {
+            precedence
+                .evaluate(expression)
+                .map_err(|e| (e.kind, e.offset()))
+        };
+        assert_eq!(error("1 << 127"), Err((ErrorKind::Overflow, 2)));
+        assert_eq!(error("1 << 200"), Err((ErrorKind::Overflow, 2)));
+        assert_eq!(error("8 >> -1"), Err((ErrorKind::NegativeShift, 2)));
+        assert_eq!(error("8 ~ 1"), Err((ErrorKind::MissingOperator, 2)));
+        let wrapping = Evaluator::with_options(
+            EvalOptions::new()
+                .syntax(Syntax::letters().with_bitwise())
+                .arithmetic(ArithmeticMode::Wrapping),
+        );
+        assert_eq!(wrapping.evaluate("3 << 127"), Ok(i128::MIN));
+
+        // operators are only known once enabled
+        assert_eq!(
+            compute("1 & 2").map_err(|e| e.kind),
+            Err(ErrorKind::UnexpectedCharacter('&'))
+        );
+    }
+
+    #[test]
+    fn conditions() {
+        let evaluator = Evaluator::new();
+        let mut env = Environment::new();
+        env.set("x", Value::Number(12));
+        env.set("y", Value::Number(3));
+        let mut value = |expression| evaluator.evaluate_value_in(expression, &mut env);
+
+        // comparisons bind looser than arithmetic even left to right
+        // (x > 10) && (y <= 1 + 2)
+        assert_eq!(value("x > 10 && y <= 1 a 2"), Ok(Value::Bool(true)));
+        assert_eq!(value("x < 10 || y != 3"), Ok(Value::Bool(false)));
+        assert_eq!(value("!ex == 12f"), Ok(Value::Bool(false)));
+        // && binds tighter than ||
+        assert_eq!(value("1 < 2 || 1 > 2 && 1 > 2"), Ok(Value::Bool(true)));
+        assert_eq!(value("1 < 2 == e2 < 1f"), Ok(Value::Bool(false)));
+
+        // x > 10 ? (y * 2) : 0
+        assert_eq!(value("x > 10 ? y c 2 : 0"), Ok(Value::Number(6)));
+        // conditions are right associative
+        assert_eq!(value("x < 5 ? 1 : x < 15 ? 2 : 3"), Ok(Value::Number(2)));
+        assert_eq!(value("x > 5 ? y > 5 ? 1 : 2 : 3"), Ok(Value::Number(2)));
+        assert_eq!(value("ok = x > 10; ok ? 1 : 0"), Ok(Value::Number(1)));
+        assert_eq!(value("z = x > 5 ? 7 : 8; z"), Ok(Value::Number(7)));
+        assert_eq!(value("max ex > 5 ? y : x, 2f"), Ok(Value::Number(3)));
+
+        // right side is never computed when left side decides
+        assert_eq!(value("y == 3 || 1 d 0 == 1"), Ok(Value::Bool(true)));
+        assert_eq!(value("y != 3 && unknown"), Ok(Value::Bool(false)));
+        assert_eq!(value("y == 3 ? 1 : 1 d 0"), Ok(Value::Number(1)));
+
+        // numeric api only accepts numbers as result
+        assert_eq!(compute("2 > 1 ? 5 : 6"), Ok(5));
+        assert_eq!(
+            compute("1 a 1 == 2").map_err(|e| (e.kind, e.span)),
+            Err((
+                ErrorKind::TypeMismatch {
+                    expected: Type::Number,
+                    found: Type::Bool
+                },
+                Span::new(0, 10)
+            ))
+        );
+
+        let error = |expression| compute(expression).map_err(|e| (e.kind, e.span));
+        let mismatch = |expected, found| ErrorKind::TypeMismatch { expected, found };
+        assert_eq!(
+            error("1 a e2 < 3f"),
+            Err((mismatch(Type::Number, Type::Bool), Span::new(4, 11)))
+        );
+        assert_eq!(
+            error("1 && 2 > 1"),
+            Err((mismatch(Type::Bool, Type::Number), Span::new(0, 1)))
+        );
+        assert_eq!(
+            error("1 > 2 || 2"),
+            Err((mismatch(Type::Bool, Type::Number), Span::new(9, 10)))
+        );
+        assert_eq!(
+            error("2 ? 1 : 0"),
+            Err((mismatch(Type::Bool, Type::Number), Span::new(0, 1)))
+        );
+        assert_eq!(
+            error("e1 < 2f == 1"),
+            Err((mismatch(Type::Bool, Type::Number), Span::new(11, 12)))
+        );
+        assert_eq!(
+            error("b e1 < 2f"),
+            Err((mismatch(Type::Number, Type::Bool), Span::new(2, 9)))
+        );
+        assert_eq!(
+            error("abs e1 < 2f"),
+            Err((mismatch(Type::Number, Type::Bool), Span::new(5, 10)))
+        );
+        assert_eq!(
+            error("!1"),
+            Err((mismatch(Type::Bool, Type::Number), Span::new(1, 2)))
+        );
+        assert_eq!(
+            error("1 ? 2"),
+            Err((ErrorKind::MissingElse, Span::new(2, 3)))
+        );
+        assert_eq!(
+            error("e1 ? 2f : 3"),
+            Err((ErrorKind::MissingElse, Span::new(3, 4)))
+        );
+        assert_eq!(
+            error("1 : 2"),
+            Err((ErrorKind::UnmatchedElse, Span::new(2, 3)))
+        );
+    }
+
+    #[test]
+    fn standard_syntax() {
+        let evaluator = Evaluator::with_options(EvalOptions::new().syntax(Syntax::standard()));
+        // same as assignment_test written with conventional symbols
+        assert_eq!(evaluator.evaluate("3+2*4"), Ok(20));
+        assert_eq!(evaluator.evaluate("3 + (4 * 66) - 32"), Ok(235));
+        assert_eq!(evaluator.evaluate("3*4/2+((2+4*41)*4)"), Ok(990));
+
+        // letters make up a variable name in standard syntax
+        let expr = evaluator.parse("3ae4c66fb32");
+        assert_eq!(
+            expr.map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::MissingOperator, Span::new(1, 11)))
+        );
+        let expr = Evaluator::new().parse("3ae4c66fb32").unwrap();
+        assert_eq!(
+            expr.display(&Syntax::standard()).to_string(),
+            "3 + (4 * 66) - 32"
+        );
+    }
+
+    #[test]
+    fn statements() {
+        let evaluator = Evaluator::new();
+        assert_eq!(evaluator.evaluate_all("3a2;5c4"), vec![Ok(5), Ok(20)]);
+        // statements do not affect each other
+        assert_eq!(
+            evaluator.evaluate_all("1 a; 2 c e3 a 1f; 4 @; 5"),
+            vec![
+                Err(EvalError::new(
+                    ErrorKind::MissingOperand,
+                    Span::of_char(3, ';')
+                )),
+                Ok(8),
+                Err(EvalError::new(
+                    ErrorKind::UnexpectedCharacter('@'),
+                    Span::of_char(20, '@')
+                )),
+                Ok(5),
+            ]
+        );
+        assert_eq!(evaluator.evaluate_all(" ; ;"), vec![]);
+
+        // single result is of the last statement
+        assert_eq!(compute("3a2;5c4"), Ok(20));
+        assert_eq!(compute("3a2;5c4;"), Ok(20));
+        assert_eq!(
+            compute("1d0;5c4").map_err(|e| e.kind),
+            Err(ErrorKind::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn variables() {
+        let evaluator = Evaluator::new();
+        // x = 3 + 2; x * 4 = 5*4 = 20
+        assert_eq!(compute("x = 3a2; x c 4"), Ok(20));
+        // assignment is an expression and binds loosest
+        // y = (x = 2 + 1) * 2 = 6
+        assert_eq!(compute("y = e x = 2 a 1 f c 2; x c y"), Ok(18));
+        assert_eq!(compute("x = y = 4; x c y"), Ok(16));
+
+        // environment outlives a single evaluation
+        let mut env = Environment::new();
+        env.set("n", Value::Number(3));
+        assert_eq!(evaluator.evaluate_in("sum = 10 c n", &mut env), Ok(30));
+        assert_eq!(
+            evaluator.evaluate_all_in("sum b 5; sum = sum c 2", &mut env),
+            vec![Ok(25), Ok(60)]
+        );
+        assert_eq!(env.get("sum"), Some(Value::Number(60)));
+
+        let error = |expression| compute(expression).map_err(|e| (e.kind, e.span));
+        assert_eq!(
+            error("x c 2"),
+            Err((ErrorKind::UndefinedVariable, Span::new(0, 1)))
+        );
+        assert_eq!(
+            error("x a 1 = 2"),
+            Err((ErrorKind::InvalidAssignment, Span::new(6, 7)))
+        );
+        assert_eq!(
+            error("3 = 2"),
+            Err((ErrorKind::InvalidAssignment, Span::new(2, 3)))
+        );
+    }
+
+    #[test]
+    fn functions() {
+        // max(3, 7) * 2 = 14
+        assert_eq!(compute("max e3, 7f c 2"), Ok(14));
+        // abs(-4) + gcd(12, 18) = 4 + 6 = 10
+        assert_eq!(compute("abs eb4f a gcd e12, 18f"), Ok(10));
+        // min(x = 5, pow(2, 3)) + x = 5 + 5 = 10
+        assert_eq!(compute("min ex = 5, pow e2, 3ff a x"), Ok(10));
+        // names end where a letter symbol starts, like other words
+        assert_eq!(compute("3cmaxe1,2f"), Ok(6));
+        assert_eq!(compute("absebb5f"), Ok(5));
+        assert_eq!(compute("gcde12,18fapowe2,3f"), Ok(14));
+
+        let evaluator = Evaluator::with_options(EvalOptions::new().syntax(Syntax::standard()))
+            .with_function("sum", Arity::AtLeast(0), |args| {
+                args.iter()
+                    .try_fold(0i128, |sum, arg| sum.checked_add(*arg))
+                    .ok_or(ErrorKind::Overflow)
+            })
+            .with_function("answer", Arity::Exact(0), |_| Ok(42));
+        assert_eq!(
+            evaluator.evaluate("sum(1, -2, max(3, 4)) * answer()"),
+            Ok(126)
+        );
+        assert_eq!(evaluator.evaluate("sum()"), Ok(0));
+
+        let error = |expression| evaluator.evaluate(expression).map_err(|e| (e.kind, e.span));
+        assert_eq!(
+            error("1 + foo(2)"),
+            Err((ErrorKind::UnknownFunction, Span::new(4, 7)))
+        );
+        assert_eq!(
+            error("pow(2) + 1"),
+            Err((
+                ErrorKind::ArityMismatch {
+                    expected: Arity::Exact(2),
+                    found: 1
+                },
+                Span::new(0, 6)
+            ))
+        );
+        assert_eq!(
+            error("1 + abs(-3, 4)").map_err(|(kind, _)| kind.to_string()),
+            Err("function takes 1 argument but 2 given".to_string())
+        );
+        assert_eq!(
+            error("2 * abs(sum(1, 2), )"),
+            Err((ErrorKind::MissingOperand, Span::new(19, 20)))
+        );
+        assert_eq!(
+            error("(1, 2)"),
+            Err((ErrorKind::UnexpectedSeparator, Span::new(2, 3)))
+        );
+        assert_eq!(
+            error("sum(1, 2"),
+            Err((ErrorKind::UnclosedParenthesis, Span::new(3, 4)))
+        );
+        assert_eq!(
+            error("sum(2) * pow(2, 127)"),
+            Err((ErrorKind::Overflow, Span::new(9, 20)))
+        );
+    }
+
+    #[test]
+    fn functions_follow_options() {
+        let standard = || EvalOptions::new().syntax(Syntax::standard());
+        let modes = [
+            ArithmeticMode::Checked,
+            ArithmeticMode::Wrapping,
+            ArithmeticMode::Saturating,
+        ];
+        for mode in modes {
+            let evaluator = Evaluator::with_options(standard().arithmetic(mode));
+            let evaluate = |expression: String| evaluator.evaluate(&expression).map_err(|e| e.kind);
+            for (base, exponent) in [("2", "200"), ("-3", "3"), ("2", "-1"), ("-7", "127")] {
+                assert_eq!(
+                    evaluate(format!("pow({base}, {exponent})")),
+                    evaluate(format!("{base} ** {exponent}")),
+                    "{mode:?} {base} ** {exponent}"
+                );
+            }
+        }
+        let wrapping = Evaluator::with_options(standard().arithmetic(ArithmeticMode::Wrapping));
+        assert_eq!(wrapping.evaluate("pow(2, 200)"), Ok(0));
+        assert_eq!(wrapping.evaluate("abs(pow(2, 127))"), Ok(i128::MIN));
+        let saturating = Evaluator::with_options(standard().arithmetic(ArithmeticMode::Saturating));
+        assert_eq!(saturating.evaluate("abs(-pow(2, 200))"), Ok(i128::MAX));
+
+        let roundings = [
+            Rounding::HalfEven,
+            Rounding::HalfUp,
+            Rounding::TowardZero,
+            Rounding::Floor,
+            Rounding::Ceiling,
+        ];
+        for rounding in roundings {
+            let money = Evaluator::<Decimal>::with_backend(standard().rounding(rounding));
+            let evaluate = |expression: String| money.evaluate(&expression).map_err(|e| e.kind);
+            for (base, exponent) in [("0.5", "3"), ("-0.5", "3"), ("3", "-1"), ("1.05", "10")] {
+                assert_eq!(
+                    evaluate(format!("pow({base}, {exponent})")),
+                    evaluate(format!("{base} ** {exponent}")),
+                    "{rounding:?} {base} ** {exponent}"
+                );
+            }
+        }
+        let ceiling = Evaluator::<Decimal>::with_backend(standard().rounding(Rounding::Ceiling));
+        assert_eq!(ceiling.evaluate("pow(0.5, 3)"), Ok(Decimal::from_units(13)));
+    }
+
+    #[test]
+    fn deep_expressions() {
+        // nesting of parenthesis is limited
+        let nested = format!("{}1{}", "e".repeat(1_000), "f".repeat(1_000));
+        assert_eq!(
+            compute(&nested).map_err(|e| e.kind),
+            Err(ErrorKind::NestingTooDeep)
+        );
+        let evaluator = Evaluator::with_options(EvalOptions::new().max_depth(1_000));
+        assert_eq!(evaluator.evaluate(&nested), Ok(1));
+
+        // long chain of operators is not nesting
+        let chain = ["1"; 100_000].join(" a ");
+        assert_eq!(compute(&chain), Ok(100_000));
+        // and it is printed, copied and compared without recursion
+        let expr = Evaluator::new().parse(&chain).unwrap();
+        assert_eq!(expr.to_string(), chain);
+        assert!(format!("{expr:?}").starts_with("BinOp { op: Add"));
+        let copy = expr.clone();
+        assert_eq!(copy, expr);
+        assert_ne!(copy, evaluator.parse(&nested).unwrap());
+    }
+
+    #[test]
+    fn long_literals() {
+        assert_eq!(compute("12345678901 a 1"), Ok(12345678902));
+        assert_eq!(
+            compute("170141183460469231731687303715884105727"),
+            Ok(i128::MAX)
+        );
+        assert_eq!(
+            compute("1 a 170141183460469231731687303715884105728 a 1")
+                .map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::LiteralOverflow, Span::new(4, 43)))
+        );
+    }
+
+    #[test]
+    fn radix_literals() {
+        assert_eq!(compute("0x1F a 0B11 c 1_0"), Ok(340));
+        assert_eq!(
+            compute("0o17 b 0x"),
+            Err(EvalError::new(ErrorKind::MissingOperator, Span::new(8, 9)))
+        );
+
+        let bitwise = EvalOptions::new().syntax(Syntax::standard().with_bitwise());
+        let evaluator = Evaluator::with_options(bitwise.clone());
+        assert_eq!(evaluator.evaluate("0xff & 0b1010_1010"), Ok(0xaa));
+        assert_eq!(evaluator.evaluate("0XFF ^ 0O7_7"), Ok(0xc0));
+
+        let hex = Evaluator::with_options(bitwise.radix(16));
+        assert_eq!(hex.evaluate("10 * 0ff"), Ok(0xff0));
+        assert_eq!(hex.evaluate("0x10 + 0o10"), Ok(0x18));
+        // `b` is a hex digit
+        assert_eq!(hex.evaluate("0B11 - 0b10"), Ok(0x1));
+        let mut env = Environment::new();
+        assert_eq!(hex.evaluate_in("ff = 1a; ff + 1", &mut env), Ok(0x1b));
+
+        let binary = Evaluator::with_options(EvalOptions::new().radix(2));
+        assert_eq!(binary.evaluate("101 a 11"), Ok(8));
+        assert_eq!(
+            binary.evaluate("101 a 2"),
+            Err(EvalError::new(
+                ErrorKind::UnexpectedCharacter('2'),
+                Span::new(6, 7)
+            ))
+        );
+
+        let unbounded = Evaluator::<BigInt>::with_backend(EvalOptions::new());
+        assert_eq!(
+            unbounded
+                .evaluate("0x1_0000_0000_0000_0000_0000_0000_0000_0000 b 1")
+                .map(|n| n.to_string()),
+            Ok(u128::MAX.to_string())
+        );
+        let floats = Evaluator::<f64>::with_backend(EvalOptions::new());
+        assert_eq!(floats.evaluate("0x10 d 0B1_000"), Ok(2.0));
+        let money = Evaluator::<Decimal>::with_backend(EvalOptions::new());
+        assert_eq!(money.evaluate("0x1_0 a 0.5"), Ok(Decimal::from_units(1650)));
+    }
+
+    #[test]
+    fn number_backends() {
+        let standard = || EvalOptions::new().syntax(Syntax::standard());
+
+        let small = Evaluator::<i64>::with_backend(standard());
+        assert_eq!(small.evaluate("-7 // 2 * 3"), Ok(-12));
+        assert_eq!(
+            small
+                .evaluate("9223372036854775807 + 1")
+                .map_err(|e| e.kind),
+            Err(ErrorKind::Overflow)
+        );
+        assert_eq!(
+            small.evaluate("9223372036854775808").map_err(|e| e.kind),
+            Err(ErrorKind::LiteralOverflow)
+        );
+
+        let unsigned = Evaluator::<u64>::with_backend(standard());
+        assert_eq!(unsigned.evaluate("-0 + 18446744073709551615"), Ok(u64::MAX));
+        assert_eq!(
+            unsigned.evaluate("1 - 2").map_err(|e| e.kind),
+            Err(ErrorKind::Overflow)
+        );
+        let wrapping =
+            Evaluator::<u64>::with_backend(standard().arithmetic(ArithmeticMode::Wrapping));
+        assert_eq!(wrapping.evaluate("-1"), Ok(u64::MAX));
+
+        let floats =
+            Evaluator::<f64>::with_backend(standard().syntax(Syntax::standard().with_bitwise()));
+        assert_eq!(floats.evaluate("1 / 4 + (2 ** -1)"), Ok(0.75));
+        assert_eq!(floats.evaluate("max(1 / 3, 1 / 2 * 0)"), Ok(1.0 / 3.0));
+        assert_eq!(floats.evaluate("gcd(12, 18) + abs(-7 % 4)"), Ok(9.0));
+        assert_eq!(
+            floats.evaluate("1 / 0").map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::DivisionByZero, Span::new(2, 3)))
+        );
+        assert_eq!(
+            floats.evaluate("1 << 2").map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::UnsupportedOperation, Span::new(2, 4)))
+        );
+
+        assert_eq!(floats.evaluate("2.5e2 * 1.5 - 1E-1"), Ok(374.9));
+        assert_eq!(
+            floats.evaluate("1 + 1e999").map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::LiteralOverflow, Span::new(4, 9)))
+        );
+        assert_eq!(
+            floats
+                .parse("0.5*2e3")
+                .map(|expr| expr.display(&Syntax::standard()).to_string()),
+            Ok("0.5 * 2000".to_string())
+        );
+
+        let exact = Evaluator::<Rational>::with_backend(standard());
+        assert_eq!(exact.evaluate("1 / 3 * 3"), Ok(Rational::from(1)));
+        assert_eq!(
+            exact.evaluate("max(1 / 3, 0.3) + (2 ** -2)"),
+            Ok(Rational::new(7, 12).unwrap())
+        );
+        assert_eq!(exact.evaluate("7 // 2 + (7 % 2.5)"), Ok(Rational::from(5)));
+        assert_eq!(
+            exact.evaluate("2 ** -200 * 1e-50 * (2 ** 200) * 1e50"),
+            Ok(Rational::from(1))
+        );
+        assert_eq!(
+            exact.evaluate("2 ** 0.5").map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::UnsupportedOperation, Span::new(2, 4)))
+        );
+
+        let unbounded =
+            Evaluator::<BigInt>::with_backend(standard().syntax(Syntax::standard().with_bitwise()));
+        let product = "121932631137021795226185032733866788594511507391563633592367367779295611949397448712086533622923332237463801111263526900";
+        assert_eq!(
+            unbounded
+                .evaluate(
+                    "123456789012345678901234567890123456789012345678901234567890 * \
+                     987654321098765432109876543210987654321098765432109876543210"
+                )
+                .map(|product| product.to_string()),
+            Ok(product.to_string())
+        );
+        assert_eq!(
+            unbounded
+                .evaluate("-(1 << 200) >> 199 == -2 && gcd(2 ** 100, 6 ** 50) == 2 ** 50 ? 1 : 0"),
+            Ok(BigInt::from(1))
+        );
+        let small = Evaluator::<BigInt>::with_backend(
+            standard()
+                .syntax(Syntax::standard().with_bitwise())
+                .max_bits(64),
+        );
+        assert_eq!(
+            small.evaluate("2 ** 63 + (1 << 62)"),
+            Ok(BigInt::from(3 << 62))
+        );
+        let too_large = small.evaluate("2 ** 64").unwrap_err();
+        assert_eq!(
+            (too_large.kind, too_large.span),
+            (ErrorKind::ResultTooLarge { max_bits: 64 }, Span::new(2, 4))
+        );
+        assert_eq!(too_large.kind.to_string(), "result exceeds 64 bits");
+        assert_eq!(
+            small.evaluate("1 << 64").map_err(|e| e.kind),
+            Err(ErrorKind::ResultTooLarge { max_bits: 64 })
+        );
+
+        let money = Evaluator::<Decimal>::with_backend(standard());
+        assert_eq!(
+            money.evaluate("19.99 * 3 - 0.5").map(|n| n.to_string()),
+            Ok("59.47".to_string())
+        );
+        assert_eq!(
+            money.evaluate("10 / 4 / 2").map(|n| n.to_string()),
+            Ok("1.25".to_string())
+        );
+        // 0.005 is a tie, to the even 0.00 by default
+        assert_eq!(money.evaluate("0.01 / 2"), Ok(Decimal::from_units(0)));
+        let half_up = Evaluator::<Decimal>::with_backend(standard().rounding(Rounding::HalfUp));
+        assert_eq!(half_up.evaluate("0.01 / 2"), Ok(Decimal::from_units(1)));
+        let floor = Evaluator::<Decimal>::with_backend(standard().rounding(Rounding::Floor));
+        assert_eq!(floor.evaluate("-1 / 3"), Ok(Decimal::from_units(-34)));
+        // literals are rounded like results
+        assert_eq!(money.evaluate("1 + 0.001"), Ok(Decimal::from_units(100)));
+        assert_eq!(half_up.evaluate("1.005"), Ok(Decimal::from_units(101)));
+        assert_eq!(floor.evaluate("1.009"), Ok(Decimal::from_units(100)));
+        assert_eq!(
+            money.evaluate("1 + 1e37").map_err(|e| (e.kind, e.span)),
+            Err((ErrorKind::LiteralOverflow, Span::new(4, 8)))
+        );
+
+        let mut env = Environment::new();
+        env.set("rate", Value::Number(0.25));
+        assert_eq!(
+            floats.evaluate_value_in("rate * 2 < 1 ? rate : 0", &mut env),
+            Ok(Value::Number(0.25))
+        );
+    }
+}
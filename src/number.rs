@@ -0,0 +1,640 @@
+//! Numeric types expressions can be computed with
+//!
+//! [`crate::Evaluator`] computes with `i128` unless another [`Number`] is picked.
+//! `i64`, `i128`, `u64` and `f64` are provided, and any other type can be plugged in
+//! by implementing the trait.
+//!
+//! Example:
+//! ```
+//! use parser_rs::{ErrorKind, EvalOptions, Evaluator};
+//!
+//! let floats = Evaluator::<f64>::with_backend(EvalOptions::new());
+//! assert_eq!(floats.evaluate("7d2"), Ok(3.5));
+//! assert_eq!(floats.evaluate("1.5e3 a 0.25"), Ok(1500.25));
+//!
+//! let unsigned = Evaluator::<u64>::with_backend(EvalOptions::new());
+//! assert_eq!(unsigned.evaluate("18446744073709551615 d 5"), Ok(3689348814741910323));
+//! assert_eq!(unsigned.evaluate("2b3").map_err(|e| e.kind), Err(ErrorKind::Overflow));
+//! ```
+
+use crate::lexer::RADIX;
+use crate::{ArithmeticMode, ErrorKind, Rounding};
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+/// Numeric type an expression is computed with
+///
+/// Every operation gets the [`ArithmeticMode`] of the evaluator
+/// and decides by itself what overflows, what divides by zero
+/// and what it does not support at all.
+/// Operations that may need rounding also get the [`Rounding`] of the evaluator.
+/// Bitwise operations are unsupported unless implemented
+pub trait Number:
+    Clone + fmt::Debug + fmt::Display + PartialEq + PartialOrd + Send + Sync + 'static
+{
+    /// Whether literals may have a fraction and an exponent, like `2.5e-3`
+    const FRACTIONAL: bool = false;
+
+    fn zero() -> Self;
+
+    /// Number written as `literal` in an expression, a run of decimal digits.
+    /// If `FRACTIONAL`, digits may be followed by `.` and more digits,
+    /// then by `e` or `E`, an optional sign and the digits of an exponent.
+    /// Digits the type does not keep are dropped by `rounding`.
+    /// Returns `None` if it can not be represented
+    fn from_literal(literal: &str, rounding: Rounding) -> Option<Self>;
+
+    /// Number written as `digits` in `radix` other than 10, like `1F` in 16.
+    /// Digits are ASCII letters or digits of any case, all valid in `radix`.
+    /// Returns `None` if it can not be represented
+    fn from_digits(digits: &str, radix: u32) -> Option<Self> {
+        let checked = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        let base = Self::from_literal(&radix.to_string(), rounding)?;
+        digits.chars().try_fold(Self::zero(), |number, ch| {
+            let digit = Self::from_literal(&ch.to_digit(radix)?.to_string(), rounding)?;
+            let shifted = number.mul(&base, checked, Rounding::default()).ok()?;
+            shifted.add(&digit, checked).ok()
+        })
+    }
+
+    fn add(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind>;
+
+    fn sub(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind>;
+
+    fn mul(&self, rhs: &Self, mode: ArithmeticMode, rounding: Rounding) -> Result<Self, ErrorKind>;
+
+    fn div(&self, rhs: &Self, mode: ArithmeticMode, rounding: Rounding) -> Result<Self, ErrorKind>;
+
+    /// Remainder of `div`
+    fn rem(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind>;
+
+    /// Euclidean modulo, never negative
+    fn rem_euclid(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind>;
+
+    /// Division rounding toward negative infinity
+    fn div_floor(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind>;
+
+    /// Types without a fixed size fail with `ErrorKind::ResultTooLarge`
+    /// rather than compute a result longer than `max_bits`
+    fn pow(
+        &self,
+        rhs: &Self,
+        mode: ArithmeticMode,
+        rounding: Rounding,
+        max_bits: usize,
+    ) -> Result<Self, ErrorKind>;
+
+    fn neg(&self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Self::zero().sub(self, mode)
+    }
+
+    fn bit_and(&self, _rhs: &Self) -> Result<Self, ErrorKind> {
+        Err(ErrorKind::UnsupportedOperation)
+    }
+
+    fn bit_or(&self, _rhs: &Self) -> Result<Self, ErrorKind> {
+        Err(ErrorKind::UnsupportedOperation)
+    }
+
+    fn bit_xor(&self, _rhs: &Self) -> Result<Self, ErrorKind> {
+        Err(ErrorKind::UnsupportedOperation)
+    }
+
+    fn bit_not(&self) -> Result<Self, ErrorKind> {
+        Err(ErrorKind::UnsupportedOperation)
+    }
+
+    /// Result longer than `max_bits` is handled like in `pow`
+    fn shl(&self, _rhs: &Self, _mode: ArithmeticMode, _max_bits: usize) -> Result<Self, ErrorKind> {
+        Err(ErrorKind::UnsupportedOperation)
+    }
+
+    fn shr(&self, _rhs: &Self) -> Result<Self, ErrorKind> {
+        Err(ErrorKind::UnsupportedOperation)
+    }
+
+    /// Sign and uppercase digits in `radix` of an integer, `(true, "1F")` for -31.
+    /// `None` if the number is not an integer or the type can not write it
+    fn to_radix(&self, _radix: u32) -> Option<(bool, String)> {
+        None
+    }
+
+    /// Written as mantissa and exponent like `1.5e3`,
+    /// with `precision` digits after the point if given
+    fn to_scientific(&self, _precision: Option<usize>) -> Option<String> {
+        None
+    }
+
+    /// Written as an exact fraction like `7/2`, or just `3` for an integer
+    fn to_fraction(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Primitive integer computed with by [`ArithmeticMode`]
+pub trait Integer:
+    Copy
+    + Ord
+    + fmt::Debug
+    + fmt::Display
+    + Not<Output = Self>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+    const BITS: u32;
+
+    fn from_digit(digit: u32) -> Self;
+    fn to_u32(self) -> Option<u32>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn wrapping_div(self, rhs: Self) -> Self;
+    fn saturating_div(self, rhs: Self) -> Self;
+    fn wrapping_rem(self, rhs: Self) -> Self;
+    fn wrapping_rem_euclid(self, rhs: Self) -> Self;
+    fn checked_pow(self, exponent: u32) -> Option<Self>;
+    fn saturating_pow(self, exponent: u32) -> Self;
+
+    fn is_negative(self) -> bool {
+        self < Self::ZERO
+    }
+
+    fn is_odd(self) -> bool {
+        self.wrapping_rem(Self::ONE.wrapping_add(Self::ONE)) != Self::ZERO
+    }
+}
+
+// `Integer` methods delegate to the inherent ones of the same name
+macro_rules! integer {
+    ($($number:ty),*) => {$(
+        impl Integer for $number {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MIN: Self = <$number>::MIN;
+            const MAX: Self = <$number>::MAX;
+            const BITS: u32 = <$number>::BITS;
+
+            fn from_digit(digit: u32) -> Self {
+                Self::from(digit as u8)
+            }
+
+            fn to_u32(self) -> Option<u32> {
+                u32::try_from(self).ok()
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$number>::checked_add(self, rhs)
+            }
+
+            fn wrapping_add(self, rhs: Self) -> Self {
+                <$number>::wrapping_add(self, rhs)
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$number>::saturating_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$number>::checked_sub(self, rhs)
+            }
+
+            fn wrapping_sub(self, rhs: Self) -> Self {
+                <$number>::wrapping_sub(self, rhs)
+            }
+
+            fn saturating_sub(self, rhs: Self) -> Self {
+                <$number>::saturating_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$number>::checked_mul(self, rhs)
+            }
+
+            fn wrapping_mul(self, rhs: Self) -> Self {
+                <$number>::wrapping_mul(self, rhs)
+            }
+
+            fn saturating_mul(self, rhs: Self) -> Self {
+                <$number>::saturating_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$number>::checked_div(self, rhs)
+            }
+
+            fn wrapping_div(self, rhs: Self) -> Self {
+                <$number>::wrapping_div(self, rhs)
+            }
+
+            fn saturating_div(self, rhs: Self) -> Self {
+                <$number>::saturating_div(self, rhs)
+            }
+
+            fn wrapping_rem(self, rhs: Self) -> Self {
+                <$number>::wrapping_rem(self, rhs)
+            }
+
+            fn wrapping_rem_euclid(self, rhs: Self) -> Self {
+                <$number>::wrapping_rem_euclid(self, rhs)
+            }
+
+            fn checked_pow(self, exponent: u32) -> Option<Self> {
+                <$number>::checked_pow(self, exponent)
+            }
+
+            fn saturating_pow(self, exponent: u32) -> Self {
+                <$number>::saturating_pow(self, exponent)
+            }
+        }
+
+        impl Number for $number {
+            fn zero() -> Self {
+                0
+            }
+
+            fn from_literal(literal: &str, _rounding: Rounding) -> Option<Self> {
+                Self::from_digits(literal, RADIX)
+            }
+
+            fn from_digits(digits: &str, radix: u32) -> Option<Self> {
+                let digits = digits
+                    .chars()
+                    .map(|ch| ch.to_digit(radix))
+                    .collect::<Option<Vec<_>>>()?;
+                combine_digit(&digits, radix)
+            }
+
+            fn add(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+                mode.add(*self, *rhs).ok_or(ErrorKind::Overflow)
+            }
+
+            fn sub(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+                mode.sub(*self, *rhs).ok_or(ErrorKind::Overflow)
+            }
+
+            fn mul(
+                &self,
+                rhs: &Self,
+                mode: ArithmeticMode,
+                _rounding: Rounding,
+            ) -> Result<Self, ErrorKind> {
+                mode.mul(*self, *rhs).ok_or(ErrorKind::Overflow)
+            }
+
+            fn div(
+                &self,
+                rhs: &Self,
+                mode: ArithmeticMode,
+                _rounding: Rounding,
+            ) -> Result<Self, ErrorKind> {
+                nonzero(*rhs)?;
+                mode.div(*self, *rhs).ok_or(ErrorKind::Overflow)
+            }
+
+            // remainder of `MIN / -1` is 0, so these never overflow
+            fn rem(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+                nonzero(*rhs)?;
+                Ok(self.wrapping_rem(*rhs))
+            }
+
+            fn rem_euclid(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+                nonzero(*rhs)?;
+                Ok(self.wrapping_rem_euclid(*rhs))
+            }
+
+            fn div_floor(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+                nonzero(*rhs)?;
+                mode.div_floor(*self, *rhs).ok_or(ErrorKind::Overflow)
+            }
+
+            fn pow(
+                &self,
+                rhs: &Self,
+                mode: ArithmeticMode,
+                _rounding: Rounding,
+                _max_bits: usize,
+            ) -> Result<Self, ErrorKind> {
+                if *self == 0 && Integer::is_negative(*rhs) {
+                    return Err(ErrorKind::DivisionByZero);
+                }
+                mode.pow(*self, *rhs).ok_or(ErrorKind::Overflow)
+            }
+
+            fn bit_and(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+                Ok(self & rhs)
+            }
+
+            fn bit_or(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+                Ok(self | rhs)
+            }
+
+            fn bit_xor(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+                Ok(self ^ rhs)
+            }
+
+            fn bit_not(&self) -> Result<Self, ErrorKind> {
+                Ok(!self)
+            }
+
+            fn shl(
+                &self,
+                rhs: &Self,
+                mode: ArithmeticMode,
+                _max_bits: usize,
+            ) -> Result<Self, ErrorKind> {
+                if Integer::is_negative(*rhs) {
+                    return Err(ErrorKind::NegativeShift);
+                }
+                mode.shl(*self, *rhs).ok_or(ErrorKind::Overflow)
+            }
+
+            fn shr(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+                if Integer::is_negative(*rhs) {
+                    return Err(ErrorKind::NegativeShift);
+                }
+                Ok(ArithmeticMode::Checked.shr(*self, *rhs))
+            }
+
+            fn to_radix(&self, radix: u32) -> Option<(bool, String)> {
+                // every supported type fits in `i128`
+                let magnitude = (*self as i128).unsigned_abs();
+                Some((Integer::is_negative(*self), radix_digits(magnitude, radix)))
+            }
+
+            fn to_fraction(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+        }
+    )*};
+}
+
+integer!(i64, i128, u64);
+
+fn nonzero<N: Integer>(divisor: N) -> Result<(), ErrorKind> {
+    match divisor == N::ZERO {
+        true => Err(ErrorKind::DivisionByZero),
+        false => Ok(()),
+    }
+}
+
+/// Convert array of digits in `radix` to number
+/// Example:
+/// input: &Vec::new([9, 8, 6, 6]), 10
+/// output: Some(9866)
+///
+/// Returns `None` if number does not fit in `N`
+fn combine_digit<N: Integer>(digits: &[u32], radix: u32) -> Option<N> {
+    digits.iter().try_fold(N::ZERO, |res, &digit| {
+        res.checked_mul(N::from_digit(radix))?
+            .checked_add(N::from_digit(digit))
+    })
+}
+
+/// Uppercase digits of `magnitude` in `radix`
+pub(crate) fn radix_digits(mut magnitude: u128, radix: u32) -> String {
+    let mut digits = vec![];
+    loop {
+        let digit = (magnitude % radix as u128) as u32;
+        digits.push(char::from_digit(digit, radix).expect("digit below radix"));
+        magnitude /= radix as u128;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect::<String>().to_uppercase()
+}
+
+/// Floats never wrap: a result too large for `f64` is an overflow,
+/// or the largest float of its sign when saturating
+impl Number for f64 {
+    const FRACTIONAL: bool = true;
+
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn from_literal(literal: &str, _rounding: Rounding) -> Option<Self> {
+        literal
+            .parse()
+            .ok()
+            .filter(|number: &f64| number.is_finite())
+    }
+
+    fn add(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        finite(self + rhs, mode)
+    }
+
+    fn sub(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        finite(self - rhs, mode)
+    }
+
+    fn mul(
+        &self,
+        rhs: &Self,
+        mode: ArithmeticMode,
+        _rounding: Rounding,
+    ) -> Result<Self, ErrorKind> {
+        finite(self * rhs, mode)
+    }
+
+    fn div(
+        &self,
+        rhs: &Self,
+        mode: ArithmeticMode,
+        _rounding: Rounding,
+    ) -> Result<Self, ErrorKind> {
+        nonzero_float(*rhs)?;
+        finite(self / rhs, mode)
+    }
+
+    fn rem(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        nonzero_float(*rhs)?;
+        Ok(self % rhs)
+    }
+
+    fn rem_euclid(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        nonzero_float(*rhs)?;
+        Ok(f64::rem_euclid(*self, *rhs))
+    }
+
+    fn div_floor(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        nonzero_float(*rhs)?;
+        finite((self / rhs).floor(), mode)
+    }
+
+    fn pow(
+        &self,
+        rhs: &Self,
+        mode: ArithmeticMode,
+        _rounding: Rounding,
+        _max_bits: usize,
+    ) -> Result<Self, ErrorKind> {
+        if *self == 0.0 && *rhs < 0.0 {
+            return Err(ErrorKind::DivisionByZero);
+        }
+        match self.powf(*rhs) {
+            // fractional power of a negative number
+            result if result.is_nan() => Err(ErrorKind::UnsupportedOperation),
+            result => finite(result, mode),
+        }
+    }
+
+    fn neg(&self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Ok(-self)
+    }
+
+    /// Only integers below 2^128
+    fn to_radix(&self, radix: u32) -> Option<(bool, String)> {
+        if self.fract() != 0.0 || self.abs() >= u128::MAX as f64 {
+            return None;
+        }
+        Some((*self < 0.0, radix_digits(self.abs() as u128, radix)))
+    }
+
+    fn to_scientific(&self, precision: Option<usize>) -> Option<String> {
+        match precision {
+            Some(precision) => Some(format!("{self:.precision$e}")),
+            None => Some(format!("{self:e}")),
+        }
+    }
+}
+
+fn nonzero_float(divisor: f64) -> Result<(), ErrorKind> {
+    match divisor == 0.0 {
+        true => Err(ErrorKind::DivisionByZero),
+        false => Ok(()),
+    }
+}
+
+fn finite(result: f64, mode: ArithmeticMode) -> Result<f64, ErrorKind> {
+    match mode {
+        _ if result.is_finite() => Ok(result),
+        ArithmeticMode::Saturating => Ok(result.clamp(f64::MIN, f64::MAX)),
+        ArithmeticMode::Checked | ArithmeticMode::Wrapping => Err(ErrorKind::Overflow),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_int::MAX_BITS;
+
+    #[test]
+    fn test_combine_digit() {
+        let combine_digit = |digits: &[u32]| combine_digit::<i128>(digits, RADIX);
+        assert_eq!(combine_digit(&[]), Some(0));
+        assert_eq!(combine_digit(&[9]), Some(9));
+        assert_eq!(combine_digit(&[1, 2]), Some(12));
+        assert_eq!(combine_digit(&[9, 8, 6, 6]), Some(9866));
+        assert_eq!(combine_digit(&[1; 11]), Some(11_111_111_111));
+        assert_eq!(combine_digit(&[9; 40]), None);
+        assert_eq!(super::combine_digit::<i128>(&[1, 15], 16), Some(31));
+        assert_eq!(super::combine_digit::<u64>(&[1; 64], 2), Some(u64::MAX));
+        assert_eq!(super::combine_digit::<i64>(&[1; 64], 2), None);
+    }
+
+    #[test]
+    fn integer_backends() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        assert_eq!(
+            i64::from_literal("9223372036854775807", Rounding::default()),
+            Some(i64::MAX)
+        );
+        assert_eq!(
+            i64::from_literal("9223372036854775808", Rounding::default()),
+            None
+        );
+        assert_eq!(
+            u64::from_literal("18446744073709551615", Rounding::default()),
+            Some(u64::MAX)
+        );
+        assert_eq!(u64::from_digits("FFFFffffFFFFffff", 16), Some(u64::MAX));
+        assert_eq!(i64::from_digits("8000000000000000", 16), None);
+        assert_eq!(i128::from_digits("1012", 2), None);
+
+        assert_eq!(Number::sub(&2u64, &3, mode), Err(ErrorKind::Overflow));
+        assert_eq!(
+            Number::sub(&2u64, &3, ArithmeticMode::Wrapping),
+            Ok(u64::MAX)
+        );
+        assert_eq!(Number::neg(&0u64, mode), Ok(0));
+        assert_eq!(Number::neg(&5u64, ArithmeticMode::Saturating), Ok(0));
+        assert_eq!(
+            Number::div(&7i64, &0, mode, rounding),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(Number::div_floor(&7u64, &2, mode), Ok(3));
+        assert_eq!(
+            Number::pow(&2u64, &63, mode, rounding, MAX_BITS),
+            Ok(1 << 63)
+        );
+        assert_eq!(
+            Number::pow(&2u64, &64, mode, rounding, MAX_BITS),
+            Err(ErrorKind::Overflow)
+        );
+        // largest u64 is no -1
+        assert_eq!(
+            Number::pow(&u64::MAX, &2, mode, rounding, MAX_BITS),
+            Err(ErrorKind::Overflow)
+        );
+        assert_eq!(Number::shl(&1u64, &63, mode, MAX_BITS), Ok(1 << 63));
+        assert_eq!(Number::shr(&u64::MAX, &70), Ok(0));
+        assert_eq!(Number::shr(&-8i64, &70), Ok(-1));
+    }
+
+    #[test]
+    fn float_backend() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        assert_eq!(f64::from_literal("12", Rounding::default()), Some(12.0));
+        assert_eq!(f64::from_literal("0.1", Rounding::default()), Some(0.1));
+        assert_eq!(
+            f64::from_literal("2.5e-3", Rounding::default()),
+            Some(0.0025)
+        );
+        assert_eq!(f64::from_literal("1E308", Rounding::default()), Some(1e308));
+        assert_eq!(f64::from_literal("1e309", Rounding::default()), None);
+        assert_eq!(f64::from_digits("ff", 16), Some(255.0));
+        assert_eq!(f64::from_digits("-1", 16), None);
+        assert_eq!(Number::div(&7.0, &2.0, mode, rounding), Ok(3.5));
+        assert_eq!(
+            Number::div(&7.0, &0.0, mode, rounding),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(Number::div_floor(&-7.0, &2.0, mode), Ok(-4.0));
+        assert_eq!(Number::rem_euclid(&-7.0, &2.0, mode), Ok(1.0));
+        assert_eq!(Number::pow(&2.0, &-1.0, mode, rounding, MAX_BITS), Ok(0.5));
+        assert_eq!(
+            Number::pow(&-8.0, &0.5, mode, rounding, MAX_BITS),
+            Err(ErrorKind::UnsupportedOperation)
+        );
+        assert_eq!(
+            Number::mul(&f64::MAX, &2.0, mode, rounding),
+            Err(ErrorKind::Overflow)
+        );
+        assert_eq!(
+            Number::mul(&f64::MAX, &-2.0, ArithmeticMode::Saturating, rounding),
+            Ok(f64::MIN)
+        );
+        assert_eq!(
+            Number::bit_and(&1.0, &3.0),
+            Err(ErrorKind::UnsupportedOperation)
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use crate::big_int::MAX_BITS;
+use crate::lexer::RADIX;
+use crate::{Integer, Operator, Syntax};
+
+/// What to do when result of an operation does not fit in the number type
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ArithmeticMode {
+    /// Stop evaluation with `ErrorKind::Overflow`
+    #[default]
+    Checked,
+    /// Wrap around at the boundary of the number type
+    Wrapping,
+    /// Clamp to the smallest or largest number
+    Saturating,
+}
+
+impl ArithmeticMode {
+    /// Returns `None` on overflow in `Checked` mode
+    pub fn add<N: Integer>(self, lhs: N, rhs: N) -> Option<N> {
+        match self {
+            ArithmeticMode::Checked => lhs.checked_add(rhs),
+            ArithmeticMode::Wrapping => Some(lhs.wrapping_add(rhs)),
+            ArithmeticMode::Saturating => Some(lhs.saturating_add(rhs)),
+        }
+    }
+
+    /// Returns `None` on overflow in `Checked` mode
+    pub fn sub<N: Integer>(self, lhs: N, rhs: N) -> Option<N> {
+        match self {
+            ArithmeticMode::Checked => lhs.checked_sub(rhs),
+            ArithmeticMode::Wrapping => Some(lhs.wrapping_sub(rhs)),
+            ArithmeticMode::Saturating => Some(lhs.saturating_sub(rhs)),
+        }
+    }
+
+    /// Returns `None` on overflow in `Checked` mode
+    pub fn mul<N: Integer>(self, lhs: N, rhs: N) -> Option<N> {
+        match self {
+            ArithmeticMode::Checked => lhs.checked_mul(rhs),
+            ArithmeticMode::Wrapping => Some(lhs.wrapping_mul(rhs)),
+            ArithmeticMode::Saturating => Some(lhs.saturating_mul(rhs)),
+        }
+    }
+
+    /// Returns `None` on overflow in `Checked` mode.
+    /// Caller must make sure `rhs` is not zero
+    pub fn div<N: Integer>(self, lhs: N, rhs: N) -> Option<N> {
+        match self {
+            ArithmeticMode::Checked => lhs.checked_div(rhs),
+            ArithmeticMode::Wrapping => Some(lhs.wrapping_div(rhs)),
+            ArithmeticMode::Saturating => Some(lhs.saturating_div(rhs)),
+        }
+    }
+
+    /// Division rounding toward negative infinity.
+    /// Returns `None` on overflow in `Checked` mode.
+    /// Caller must make sure `rhs` is not zero
+    pub fn div_floor<N: Integer>(self, lhs: N, rhs: N) -> Option<N> {
+        let quotient = self.div(lhs, rhs)?;
+        // inexact quotient of operands with different signs is rounded up.
+        // It is never `N::MIN` then, so stepping down can not overflow
+        if lhs.wrapping_rem(rhs) != N::ZERO && lhs.is_negative() != rhs.is_negative() {
+            Some(quotient.wrapping_sub(N::ONE))
+        } else {
+            Some(quotient)
+        }
+    }
+
+    /// `lhs` shifted left by `rhs` bits, that is multiplied by 2 to the power of `rhs`.
+    /// Shifting out any bit that differs from the sign is an overflow.
+    /// Returns `None` on overflow in `Checked` mode.
+    /// Caller must make sure `rhs` is not negative
+    pub fn shl<N: Integer>(self, lhs: N, rhs: N) -> Option<N> {
+        let (shifted, overflow) = match rhs.to_u32() {
+            Some(rhs) if rhs < N::BITS => (lhs << rhs, (lhs << rhs) >> rhs != lhs),
+            _ => (N::ZERO, lhs != N::ZERO),
+        };
+        match self {
+            _ if !overflow => Some(shifted),
+            ArithmeticMode::Checked => None,
+            ArithmeticMode::Wrapping => Some(shifted),
+            ArithmeticMode::Saturating if lhs.is_negative() => Some(N::MIN),
+            ArithmeticMode::Saturating => Some(N::MAX),
+        }
+    }
+
+    /// `lhs` shifted right by `rhs` bits, that is divided by 2 to the power of `rhs`
+    /// rounding down. Never overflows.
+    /// Caller must make sure `rhs` is not negative
+    pub fn shr<N: Integer>(self, lhs: N, rhs: N) -> N {
+        match rhs.to_u32() {
+            Some(rhs) if rhs < N::BITS => lhs >> rhs,
+            // every bit is the sign
+            _ if lhs.is_negative() => !N::ZERO,
+            _ => N::ZERO,
+        }
+    }
+
+    /// `lhs` raised to `rhs`.
+    /// Negative exponent truncates the fraction like division does.
+    /// Returns `None` on overflow in `Checked` mode.
+    /// Caller must make sure `lhs` is not zero when `rhs` is negative
+    pub fn pow<N: Integer>(self, lhs: N, rhs: N) -> Option<N> {
+        let minus_one = N::ZERO.wrapping_sub(N::ONE);
+        let unit = lhs == N::ONE || (minus_one.is_negative() && lhs == minus_one);
+        // only 1 and -1 have a power with negative exponent that is not a fraction
+        if rhs.is_negative() || unit || lhs == N::ZERO {
+            return match () {
+                _ if unit && lhs != N::ONE && rhs.is_odd() => Some(minus_one),
+                _ if unit || rhs == N::ZERO => Some(N::ONE),
+                _ => Some(N::ZERO),
+            };
+        }
+        match (self, rhs.to_u32()) {
+            (ArithmeticMode::Checked, Some(rhs)) => lhs.checked_pow(rhs),
+            (ArithmeticMode::Saturating, Some(rhs)) => Some(lhs.saturating_pow(rhs)),
+            (ArithmeticMode::Checked, None) => None,
+            (ArithmeticMode::Saturating, None) if lhs.is_negative() && rhs.is_odd() => Some(N::MIN),
+            (ArithmeticMode::Saturating, None) => Some(N::MAX),
+            // exponentiation by squaring
+            (ArithmeticMode::Wrapping, _) => {
+                let (mut base, mut exponent, mut result) = (lhs, rhs, N::ONE);
+                while exponent > N::ZERO {
+                    if exponent.is_odd() {
+                        result = result.wrapping_mul(base);
+                    }
+                    base = base.wrapping_mul(base);
+                    exponent = exponent >> 1;
+                }
+                Some(result)
+            }
+        }
+    }
+}
+
+/// Which way to go when result of an operation or a literal falls between two numbers
+/// the number type can represent, like a [`crate::Decimal`] with too many fraction digits.
+/// Exact number types never round
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Rounding {
+    /// To the nearest, ties to the even neighbour. `0.125` is `0.12`
+    #[default]
+    HalfEven,
+    /// To the nearest, ties away from zero. `0.125` is `0.13`
+    HalfUp,
+    /// Drop the extra digits. `-0.129` is `-0.12`
+    TowardZero,
+    /// Toward negative infinity. `-0.121` is `-0.13`
+    Floor,
+    /// Toward positive infinity. `0.121` is `0.13`
+    Ceiling,
+}
+
+/// Order in which operators of an expression are computed
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Strategy {
+    /// Unless in parenthesis, compute strictly from left to right.
+    /// `3a2c4` is `(3 + 2) * 4` = 20
+    ///
+    /// Comparisons still bind looser than other operators,
+    /// then `&&` and loosest `||`, so conditions need no parenthesis.
+    /// `x > 1a2 && y < 3` is `(x > (1 + 2)) && (y < 3)`
+    #[default]
+    LeftToRight,
+    /// Multiplication and division bind tighter than addition and subtraction.
+    /// `3a2c4` is `3 + (2 * 4)` = 11
+    ///
+    /// Exponent binds tightest, even tighter than a sign before it,
+    /// and is right associative.
+    /// `b2i2i3` is `-(2 ** (2 ** 3))` = -256
+    ///
+    /// Bitwise operators bind looser than arithmetic ones,
+    /// from tightest: shifts, `&`, `^`, `|`.
+    /// Then come comparisons, `&&` and loosest `||`
+    Precedence,
+}
+
+impl Strategy {
+    /// Binding power of `operator`. Higher binds tighter.
+    /// End of expression binds loosest with power of 0
+    pub fn precedence(self, operator: Operator) -> u8 {
+        match (self, operator) {
+            (_, Operator::Or) => 1,
+            (_, Operator::And) => 2,
+            (
+                _,
+                Operator::Lt
+                | Operator::Le
+                | Operator::Gt
+                | Operator::Ge
+                | Operator::Eq
+                | Operator::Ne,
+            ) => 3,
+            (Strategy::LeftToRight, _) => 4,
+            (Strategy::Precedence, Operator::BitOr) => 4,
+            (Strategy::Precedence, Operator::BitXor) => 5,
+            (Strategy::Precedence, Operator::BitAnd) => 6,
+            (Strategy::Precedence, Operator::Shl | Operator::Shr) => 7,
+            (Strategy::Precedence, Operator::Add | Operator::Sub) => 8,
+            (
+                Strategy::Precedence,
+                Operator::Mul | Operator::Div | Operator::Rem | Operator::Mod | Operator::FloorDiv,
+            ) => 9,
+            (Strategy::Precedence, Operator::Pow) => 10,
+        }
+    }
+
+    /// Binding power of a sign before an operand
+    pub fn sign_precedence(self) -> u8 {
+        match self {
+            Strategy::LeftToRight => u8::MAX,
+            Strategy::Precedence => self.precedence(Operator::Pow),
+        }
+    }
+
+    /// Whether `a op b op c` is `a op (b op c)`
+    pub fn is_right_associative(self, operator: Operator) -> bool {
+        self == Strategy::Precedence && operator == Operator::Pow
+    }
+}
+
+/// Knobs controlling how [`crate::Evaluator`] computes an expression
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EvalOptions {
+    pub syntax: Syntax,
+    /// Radix of number literals without a `0x`, `0o` or `0b` prefix
+    pub radix: u32,
+    pub arithmetic: ArithmeticMode,
+    pub rounding: Rounding,
+    pub strategy: Strategy,
+    /// How many parenthesis can be open at once
+    /// before parsing stops with `ErrorKind::NestingTooDeep`
+    pub max_depth: usize,
+    /// Longest result of a power or shift of number types without a fixed size,
+    /// like [`crate::BigInt`], before evaluation stops with `ErrorKind::ResultTooLarge`
+    pub max_bits: usize,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        EvalOptions {
+            syntax: Syntax::default(),
+            radix: RADIX,
+            arithmetic: ArithmeticMode::default(),
+            rounding: Rounding::default(),
+            strategy: Strategy::default(),
+            max_depth: 256,
+            max_bits: MAX_BITS,
+        }
+    }
+}
+
+impl EvalOptions {
+    pub fn new() -> Self {
+        EvalOptions::default()
+    }
+
+    pub fn syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Read literals without a prefix in `radix`, like `0FF` in 16.
+    /// A literal still starts with a digit from 0 to 9, `FF` is a variable name.
+    /// Prefixes `0x`, `0o` and `0b` pick their own radix unless their letter is a digit
+    /// of `radix`, so in radix 16 `0B11` is `0xB11` and binary is written `0b` only below 12
+    ///
+    /// Panics if `radix` is not in `2..=36`
+    pub fn radix(mut self, radix: u32) -> Self {
+        assert!((2..=36).contains(&radix), "Invalid radix: {radix}");
+        self.radix = radix;
+        self
+    }
+
+    pub fn arithmetic(mut self, arithmetic: ArithmeticMode) -> Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn max_bits(mut self, max_bits: usize) -> Self {
+        self.max_bits = max_bits;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Number = i128;
+
+    #[test]
+    fn overflow_semantics() {
+        let max = Number::MAX;
+        assert_eq!(ArithmeticMode::Checked.add(max, 1), None);
+        assert_eq!(ArithmeticMode::Wrapping.add(max, 1), Some(Number::MIN));
+        assert_eq!(ArithmeticMode::Saturating.add(max, 1), Some(max));
+
+        assert_eq!(ArithmeticMode::Checked.sub(Number::MIN, 1), None);
+        assert_eq!(ArithmeticMode::Wrapping.sub(Number::MIN, 1), Some(max));
+        assert_eq!(
+            ArithmeticMode::Saturating.sub(Number::MIN, 1),
+            Some(Number::MIN)
+        );
+
+        assert_eq!(ArithmeticMode::Checked.mul(max, 2), None);
+        assert_eq!(ArithmeticMode::Wrapping.mul(max, 2), Some(-2));
+        assert_eq!(ArithmeticMode::Saturating.mul(max, -2), Some(Number::MIN));
+
+        assert_eq!(ArithmeticMode::Checked.div(Number::MIN, -1), None);
+        assert_eq!(
+            ArithmeticMode::Wrapping.div(Number::MIN, -1),
+            Some(Number::MIN)
+        );
+        assert_eq!(ArithmeticMode::Saturating.div(Number::MIN, -1), Some(max));
+
+        assert_eq!(ArithmeticMode::Checked.pow(2 as Number, 127), None);
+        assert_eq!(ArithmeticMode::Wrapping.pow(2, 127), Some(Number::MIN));
+        assert_eq!(ArithmeticMode::Wrapping.pow(2, max), Some(0));
+        assert_eq!(ArithmeticMode::Saturating.pow(-2, 127), Some(Number::MIN));
+        assert_eq!(ArithmeticMode::Saturating.pow(-2, max), Some(Number::MIN));
+    }
+
+    #[test]
+    fn shifts() {
+        let max = Number::MAX;
+        assert_eq!(ArithmeticMode::Checked.shl(3 as Number, 4), Some(48));
+        assert_eq!(ArithmeticMode::Checked.shl(-1, 127), Some(Number::MIN));
+        assert_eq!(ArithmeticMode::Checked.shl(0 as Number, 500), Some(0));
+        assert_eq!(ArithmeticMode::Checked.shl(1 as Number, 127), None);
+        assert_eq!(ArithmeticMode::Checked.shl(1 as Number, 128), None);
+        assert_eq!(ArithmeticMode::Wrapping.shl(3, 127), Some(Number::MIN));
+        assert_eq!(ArithmeticMode::Wrapping.shl(1 as Number, 128), Some(0));
+        assert_eq!(ArithmeticMode::Saturating.shl(1, 127), Some(max));
+        assert_eq!(ArithmeticMode::Saturating.shl(-3, 200), Some(Number::MIN));
+
+        assert_eq!(ArithmeticMode::Checked.shr(-7 as Number, 1), -4);
+        assert_eq!(ArithmeticMode::Checked.shr(max, 500), 0);
+        assert_eq!(ArithmeticMode::Checked.shr(-1 as Number, 500), -1);
+    }
+
+    #[test]
+    fn pow_and_div_floor() {
+        let mode = ArithmeticMode::Checked;
+        assert_eq!(mode.pow(2 as Number, 10), Some(1024));
+        assert_eq!(mode.pow(-3 as Number, 3), Some(-27));
+        assert_eq!(mode.pow(0 as Number, 0), Some(1));
+        assert_eq!(mode.pow(0 as Number, 5), Some(0));
+        assert_eq!(mode.pow(2 as Number, -1), Some(0));
+        assert_eq!(mode.pow(-1 as Number, -3), Some(-1));
+        assert_eq!(mode.pow(-1, Number::MAX), Some(-1));
+        assert_eq!(mode.pow(2, Number::MAX), None);
+
+        assert_eq!(mode.div_floor(7 as Number, 2), Some(3));
+        assert_eq!(mode.div_floor(-7 as Number, 2), Some(-4));
+        assert_eq!(mode.div_floor(7 as Number, -2), Some(-4));
+        assert_eq!(mode.div_floor(-7 as Number, -2), Some(3));
+        assert_eq!(mode.div_floor(-8 as Number, 2), Some(-4));
+        assert_eq!(mode.div_floor(Number::MIN, -1), None);
+        assert_eq!(mode.div_floor(Number::MIN, 3), Some(Number::MIN / 3 - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid radix")]
+    fn radix_is_checked() {
+        let _ = EvalOptions::new().radix(37);
+    }
+}
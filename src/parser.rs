@@ -0,0 +1,696 @@
+//! Build an [`Expr`] tree out of [`Token`]s
+//!
+//! Example:
+//! ```
+//! use parser_rs::{EvalOptions, Expr, Parser};
+//!
+//! let expr = Parser::new("3 a 2 c 4", &EvalOptions::new()).parse().unwrap();
+//! assert!(matches!(expr, Expr::BinOp { .. }));
+//! assert_eq!(expr.to_string(), "3 a 2 c 4");
+//! ```
+
+use crate::{
+    ErrorKind, EvalError, EvalOptions, Expr, Lexer, Number, Operator, Span, Token, TokenKind,
+    UnaryOperator,
+};
+
+/// Operator waiting on the stack for its right hand side
+#[derive(Clone, Debug)]
+enum Pending {
+    Binary {
+        op: Operator,
+        span: Span,
+    },
+    Neg {
+        span: Span,
+    },
+    Unary {
+        op: UnaryOperator,
+        span: Span,
+    },
+    /// Assignment to variable `name`
+    Assign {
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+    /// Open parenthesis
+    Group {
+        span: Span,
+    },
+    /// Condition waiting for its `Symbol::Else`
+    Then {
+        span: Span,
+    },
+    /// Condition and value if it is true, waiting for value if it is false
+    Else,
+    /// Open parenthesis of a call to function `name`
+    /// `args` is the number of arguments already separated
+    Call {
+        name: String,
+        name_span: Span,
+        span: Span,
+        args: usize,
+    },
+}
+
+/// Parses the whole expression in a single pass over its tokens.
+///
+/// Nesting is tracked with explicit operand and operator stacks
+/// instead of recursion, so deeply nested input can not overflow
+/// the call stack. Depth is still limited by `EvalOptions::max_depth`
+///
+/// Expression may be made of several statements separated by
+/// `Symbol::EndStatement`, each parsed independently of others
+pub struct Parser<'a, N = i128> {
+    lexer: Lexer<'a, N>,
+    options: &'a EvalOptions,
+    operands: Vec<Expr<N>>,
+    operators: Vec<Pending>,
+    /// Number of `Pending::Group` and `Pending::Call` in `operators`
+    depth: usize,
+    /// Last consumed token ended a statement
+    at_boundary: bool,
+    /// Where last consumed token appeared in the expression
+    last_span: Span,
+    /// `TokenKind::End` is consumed
+    finished: bool,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str, options: &'a EvalOptions) -> Self {
+        Parser::with_backend(source, options)
+    }
+}
+
+impl<'a, N: Number> Parser<'a, N> {
+    /// Parser of numbers of type `N`
+    pub fn with_backend(source: &'a str, options: &'a EvalOptions) -> Self {
+        Parser {
+            lexer: Lexer::with_backend(source, &options.syntax)
+                .radix(options.radix)
+                .rounding(options.rounding),
+            options,
+            operands: vec![],
+            operators: vec![],
+            depth: 0,
+            at_boundary: false,
+            last_span: Span::default(),
+            finished: false,
+        }
+    }
+
+    /// Parse expression made of a single statement
+    pub fn parse(mut self) -> Result<Expr<N>, EvalError> {
+        let expr = match self.next_statement() {
+            Some(statement) => statement?,
+            // empty expression is 0
+            None => Expr::Num {
+                value: N::zero(),
+                span: Span::empty(0),
+            },
+        };
+        // trailing empty statements are fine
+        let separator = self.last_span;
+        if self.next_statement().is_some() {
+            return Err(EvalError::new(ErrorKind::MultipleStatements, separator));
+        }
+        Ok(expr)
+    }
+
+    /// Parse next non-empty statement.
+    /// Returns `None` once every statement is parsed
+    ///
+    /// After an error, rest of the failed statement is skipped
+    /// so parsing can continue with the next one
+    pub fn next_statement(&mut self) -> Option<Result<Expr<N>, EvalError>> {
+        while !self.finished {
+            match self.statement() {
+                Ok(Some(expr)) => return Some(Ok(expr)),
+                Ok(None) => {}
+                Err(error) => {
+                    self.operands.clear();
+                    self.operators.clear();
+                    self.depth = 0;
+                    if !self.at_boundary {
+                        self.lexer.skip_statement();
+                    }
+                    return Some(Err(error));
+                }
+            }
+        }
+        None
+    }
+
+    /// Parse tokens up to the end of current statement.
+    /// Returns `None` if statement is empty
+    fn statement(&mut self) -> Result<Option<Expr<N>>, EvalError> {
+        // start of statement and of every parenthesis may be completely empty
+        let mut at_start = true;
+        let mut expect_operand = true;
+        // name followed by parenthesis is a function call
+        let mut after_name = false;
+
+        loop {
+            self.at_boundary = false;
+            // lexer always finishes with either error or `TokenKind::End`
+            let Token { kind, span } = self.lexer.next().expect("token after end of expression")?;
+            self.last_span = span;
+            let follows_name = after_name;
+            after_name = matches!(kind, TokenKind::Ident(_));
+            let ends_statement = matches!(kind, TokenKind::EndStatement | TokenKind::End);
+            self.at_boundary = ends_statement;
+            self.finished = kind == TokenKind::End;
+
+            if expect_operand {
+                let is_rparen = kind == TokenKind::RParen;
+                match kind {
+                    TokenKind::Number(ref value) => {
+                        self.operands.push(Expr::Num {
+                            value: value.clone(),
+                            span,
+                        });
+                        expect_operand = false;
+                    }
+                    TokenKind::Ident(ref name) => {
+                        self.operands.push(Expr::Var {
+                            name: name.clone(),
+                            span,
+                        });
+                        expect_operand = false;
+                    }
+                    TokenKind::LParen => {
+                        self.open(Pending::Group { span })?;
+                        at_start = true;
+                        continue;
+                    }
+                    // every operand may have any number of signs,
+                    // binding tighter than any binary operator
+                    TokenKind::Op(Operator::Add) => {}
+                    TokenKind::Op(Operator::Sub) => {
+                        self.operators.push(Pending::Neg { span });
+                    }
+                    TokenKind::Unary(op) => self.operators.push(Pending::Unary { op, span }),
+                    _ if ends_statement && at_start && self.operators.is_empty() => {
+                        return Ok(None);
+                    }
+                    TokenKind::RParen
+                        if at_start
+                            && matches!(
+                                self.operators.last(),
+                                Some(Pending::Call { args: 0, .. })
+                            ) =>
+                    {
+                        self.close_call(0, span);
+                        expect_operand = false;
+                        at_start = false;
+                        continue;
+                    }
+                    TokenKind::RParen
+                        if at_start
+                            && matches!(self.operators.last(), Some(Pending::Group { .. })) =>
+                    {
+                        // empty parenthesis is 0
+                        self.operands.push(Expr::Num {
+                            value: N::zero(),
+                            span: Span::empty(span.start),
+                        });
+                        expect_operand = false;
+                    }
+                    _ => return Err(EvalError::new(ErrorKind::MissingOperand, span)),
+                }
+                at_start = false;
+                // closing parenthesis of an empty group is handled below
+                if expect_operand || !is_rparen {
+                    continue;
+                }
+            }
+
+            match kind {
+                TokenKind::Op(op) => {
+                    let strategy = self.options.strategy;
+                    let precedence = strategy.precedence(op);
+                    // right associative operator leaves operators
+                    // binding as tight on the stack
+                    if strategy.is_right_associative(op) {
+                        self.reduce(precedence + 1);
+                    } else {
+                        self.reduce(precedence);
+                    }
+                    self.operators.push(Pending::Binary { op, span });
+                    expect_operand = true;
+                }
+                TokenKind::Assign => {
+                    // assignment binds loosest and is right associative.
+                    // Everything before it must make up a single variable
+                    self.reduce(1);
+                    let target = self.pop_operand();
+                    let Expr::Var {
+                        name,
+                        span: name_span,
+                    } = &target
+                    else {
+                        return Err(EvalError::new(ErrorKind::InvalidAssignment, span));
+                    };
+                    self.operators.push(Pending::Assign {
+                        name: name.clone(),
+                        name_span: *name_span,
+                        span,
+                    });
+                    expect_operand = true;
+                }
+                TokenKind::LParen if follows_name => {
+                    let target = self.pop_operand();
+                    let Expr::Var {
+                        name,
+                        span: name_span,
+                    } = &target
+                    else {
+                        unreachable!("name is a variable until followed by parenthesis");
+                    };
+                    self.open(Pending::Call {
+                        name: name.clone(),
+                        name_span: *name_span,
+                        span,
+                        args: 0,
+                    })?;
+                    expect_operand = true;
+                    at_start = true;
+                }
+                TokenKind::Then => {
+                    // condition binds looser than any operator but assignment
+                    self.reduce(1);
+                    self.operators.push(Pending::Then { span });
+                    expect_operand = true;
+                }
+                TokenKind::Else => {
+                    self.reduce(0);
+                    match self.operators.last_mut() {
+                        Some(pending @ Pending::Then { .. }) => *pending = Pending::Else,
+                        _ => return Err(EvalError::new(ErrorKind::UnmatchedElse, span)),
+                    }
+                    expect_operand = true;
+                }
+                TokenKind::ArgSeparator => {
+                    self.reduce_all()?;
+                    let Some(Pending::Call { args, .. }) = self.operators.last_mut() else {
+                        return Err(EvalError::new(ErrorKind::UnexpectedSeparator, span));
+                    };
+                    *args += 1;
+                    expect_operand = true;
+                    // argument may have a sign
+                    at_start = true;
+                }
+                TokenKind::RParen => {
+                    self.reduce_all()?;
+                    match self.operators.last() {
+                        Some(Pending::Group { span: open_span }) => {
+                            let open_span = *open_span;
+                            self.operators.pop();
+                            self.depth -= 1;
+                            let inner = self.pop_operand();
+                            self.operands.push(Expr::Group {
+                                inner: Box::new(inner),
+                                span: open_span.to(span),
+                            });
+                        }
+                        Some(Pending::Call { args, .. }) => self.close_call(args + 1, span),
+                        _ => return Err(EvalError::new(ErrorKind::UnmatchedParenthesis, span)),
+                    }
+                }
+                TokenKind::EndStatement | TokenKind::End => {
+                    self.reduce_all()?;
+                    // report the outermost parenthesis left open
+                    let outermost_group = self.operators.iter().find_map(|pending| match pending {
+                        Pending::Group { span } | Pending::Call { span, .. } => Some(*span),
+                        _ => None,
+                    });
+                    if let Some(span) = outermost_group {
+                        return Err(EvalError::new(ErrorKind::UnclosedParenthesis, span));
+                    }
+                    return Ok(Some(self.pop_operand()));
+                }
+                TokenKind::Number(_)
+                | TokenKind::Ident(_)
+                | TokenKind::LParen
+                | TokenKind::Unary(_) => {
+                    return Err(EvalError::new(ErrorKind::MissingOperator, span));
+                }
+            }
+        }
+    }
+
+    /// Build every pending operator that binds at least as tight as `min_precedence`
+    /// Stops at an open parenthesis
+    fn reduce(&mut self, min_precedence: u8) {
+        let strategy = self.options.strategy;
+        while let Some(pending) = self.operators.last() {
+            let precedence = match pending {
+                Pending::Group { .. } | Pending::Call { .. } | Pending::Then { .. } => break,
+                Pending::Neg { .. } | Pending::Unary { .. } => strategy.sign_precedence(),
+                Pending::Binary { op, .. } => strategy.precedence(*op),
+                // conditions are right associative,
+                // so nothing else is ever reduced at this precedence
+                Pending::Assign { .. } | Pending::Else => 0,
+            };
+            // operators binding as tight are built first,
+            // making them left associative
+            if precedence < min_precedence {
+                break;
+            }
+
+            let expr = match self.operators.pop().expect("pending operator") {
+                Pending::Group { .. } | Pending::Call { .. } | Pending::Then { .. } => {
+                    unreachable!("open parenthesis or condition is never reduced")
+                }
+                Pending::Else => {
+                    let otherwise = self.pop_operand();
+                    let then = self.pop_operand();
+                    Expr::Conditional {
+                        condition: Box::new(self.pop_operand()),
+                        then: Box::new(then),
+                        otherwise: Box::new(otherwise),
+                    }
+                }
+                Pending::Neg { span } => Expr::Neg {
+                    operand: Box::new(self.pop_operand()),
+                    op_span: span,
+                },
+                Pending::Unary { op, span } => Expr::Unary {
+                    op,
+                    op_span: span,
+                    operand: Box::new(self.pop_operand()),
+                },
+                Pending::Binary { op, span } => {
+                    let rhs = self.pop_operand();
+                    let lhs = self.pop_operand();
+                    Expr::BinOp {
+                        op,
+                        op_span: span,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    }
+                }
+                Pending::Assign {
+                    name,
+                    name_span,
+                    span,
+                } => Expr::Assign {
+                    name,
+                    name_span,
+                    op_span: span,
+                    value: Box::new(self.pop_operand()),
+                },
+            };
+            self.operands.push(expr);
+        }
+    }
+
+    /// Build every pending operator up to the innermost open parenthesis.
+    /// Condition without its `Symbol::Else` can not be built
+    fn reduce_all(&mut self) -> Result<(), EvalError> {
+        self.reduce(0);
+        match self.operators.last() {
+            Some(Pending::Then { span }) => Err(EvalError::new(ErrorKind::MissingElse, *span)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Push an open parenthesis, unless it nests too deep
+    fn open(&mut self, pending: Pending) -> Result<(), EvalError> {
+        if self.depth == self.options.max_depth {
+            return Err(EvalError::new(ErrorKind::NestingTooDeep, self.last_span));
+        }
+        self.depth += 1;
+        self.operators.push(pending);
+        Ok(())
+    }
+
+    /// Build call of the function on top of `operators`
+    /// taking its last `count` operands as arguments
+    fn close_call(&mut self, count: usize, close_span: Span) {
+        let Some(Pending::Call {
+            name, name_span, ..
+        }) = self.operators.pop()
+        else {
+            unreachable!("call is open");
+        };
+        self.depth -= 1;
+        let args = self.operands.split_off(self.operands.len() - count);
+        self.operands.push(Expr::Call {
+            name,
+            name_span,
+            args,
+            span: name_span.to(close_span),
+        });
+    }
+
+    /// Every pending operator has its operands pushed before it is built
+    fn pop_operand(&mut self) -> Expr<N> {
+        self.operands.pop().expect("operand of pending operator")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Strategy, Syntax};
+
+    fn parse(source: &str, strategy: Strategy) -> Expr {
+        Parser::new(source, &EvalOptions::new().strategy(strategy))
+            .parse()
+            .unwrap()
+    }
+
+    fn num(value: i128, start: usize) -> Box<Expr> {
+        let span = Span::new(start, start + value.to_string().len());
+        Box::new(Expr::Num { value, span })
+    }
+
+    #[test]
+    fn tree_shape() {
+        // (1 a 2) c 3
+        assert_eq!(
+            parse("1a2c3", Strategy::LeftToRight),
+            Expr::BinOp {
+                op: Operator::Mul,
+                op_span: Span::new(3, 4),
+                lhs: Box::new(Expr::BinOp {
+                    op: Operator::Add,
+                    op_span: Span::new(1, 2),
+                    lhs: num(1, 0),
+                    rhs: num(2, 2),
+                }),
+                rhs: num(3, 4),
+            }
+        );
+        // 1 a (2 c 3)
+        assert_eq!(
+            parse("1a2c3", Strategy::Precedence),
+            Expr::BinOp {
+                op: Operator::Add,
+                op_span: Span::new(1, 2),
+                lhs: num(1, 0),
+                rhs: Box::new(Expr::BinOp {
+                    op: Operator::Mul,
+                    op_span: Span::new(3, 4),
+                    lhs: num(2, 2),
+                    rhs: num(3, 4),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn group_and_sign() {
+        assert_eq!(
+            parse("eb12f", Strategy::LeftToRight),
+            Expr::Group {
+                inner: Box::new(Expr::Neg {
+                    operand: num(12, 2),
+                    op_span: Span::new(1, 2),
+                }),
+                span: Span::new(0, 5),
+            }
+        );
+
+        // 3 c (b(b2))
+        assert_eq!(
+            parse("3 c bb2", Strategy::Precedence),
+            Expr::BinOp {
+                op: Operator::Mul,
+                op_span: Span::new(2, 3),
+                lhs: num(3, 0),
+                rhs: Box::new(Expr::Neg {
+                    operand: Box::new(Expr::Neg {
+                        operand: num(2, 6),
+                        op_span: Span::new(5, 6),
+                    }),
+                    op_span: Span::new(4, 5),
+                }),
+            }
+        );
+        // plus sign leaves operand as is
+        assert_eq!(parse("a e a4f", Strategy::LeftToRight).to_string(), "e4f");
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let nested = |depth| format!("{}1{}", "e".repeat(depth), "f".repeat(depth));
+        let parse = |source: &str, max_depth| {
+            Parser::new(source, &EvalOptions::new().max_depth(max_depth)).parse()
+        };
+
+        assert!(parse(&nested(3), 3).is_ok());
+        assert_eq!(
+            parse(&nested(4), 3),
+            Err(EvalError::new(ErrorKind::NestingTooDeep, Span::new(3, 4)))
+        );
+        // limit applies to parenthesis open at once, not in total
+        assert!(parse(&["e1f"; 10_000].join(" a "), 1).is_ok());
+    }
+
+    #[test]
+    fn statements() {
+        let options = EvalOptions::new();
+        let mut parser = Parser::new("1 a 2; ;e3f;; b4;", &options);
+        assert_eq!(
+            parser.next_statement().unwrap().unwrap().to_string(),
+            "1 a 2"
+        );
+        assert_eq!(parser.next_statement().unwrap().unwrap().to_string(), "e3f");
+        assert_eq!(parser.next_statement().unwrap().unwrap().to_string(), "b4");
+        assert_eq!(parser.next_statement(), None);
+
+        assert!(Parser::new("; 1; ;", &options).parse().is_ok());
+        assert_eq!(
+            Parser::new("1; 2", &options).parse(),
+            Err(EvalError::new(
+                ErrorKind::MultipleStatements,
+                Span::new(1, 2)
+            ))
+        );
+    }
+
+    #[test]
+    fn recover_after_error() {
+        let options = EvalOptions::new();
+        let mut parser = Parser::new("1 @ 2; 1 a; e1; 3f; 4", &options);
+        let mut errors = vec![];
+        while let Some(statement) = parser.next_statement() {
+            errors.push(statement.map_err(|e| (e.kind, e.offset())));
+        }
+
+        assert_eq!(errors.len(), 5);
+        assert_eq!(errors[0], Err((ErrorKind::UnexpectedCharacter('@'), 2)));
+        assert_eq!(errors[1], Err((ErrorKind::MissingOperand, 10)));
+        assert_eq!(errors[2], Err((ErrorKind::UnclosedParenthesis, 12)));
+        assert_eq!(errors[3], Err((ErrorKind::UnmatchedParenthesis, 17)));
+        assert!(errors[4].is_ok());
+    }
+
+    #[test]
+    fn assignment() {
+        // x = (y = (1 a 2))
+        assert_eq!(
+            parse("x = y = 1a2", Strategy::LeftToRight),
+            Expr::Assign {
+                name: "x".to_string(),
+                name_span: Span::new(0, 1),
+                op_span: Span::new(2, 3),
+                value: Box::new(Expr::Assign {
+                    name: "y".to_string(),
+                    name_span: Span::new(4, 5),
+                    op_span: Span::new(6, 7),
+                    value: Box::new(Expr::BinOp {
+                        op: Operator::Add,
+                        op_span: Span::new(9, 10),
+                        lhs: num(1, 8),
+                        rhs: num(2, 10),
+                    }),
+                }),
+            }
+        );
+
+        let error = |source| {
+            Parser::new(source, &EvalOptions::new())
+                .parse()
+                .map_err(|e| (e.kind, e.offset()))
+        };
+        assert_eq!(error("e x f = 1"), Err((ErrorKind::InvalidAssignment, 6)));
+        assert_eq!(error("x = "), Err((ErrorKind::MissingOperand, 4)));
+    }
+
+    #[test]
+    fn calls() {
+        let options = EvalOptions::new().syntax(Syntax::standard());
+        let parse = |source| Parser::new(source, &options).parse();
+
+        // f() + g(1, -x)
+        assert_eq!(
+            parse("f() + g(1, -x)"),
+            Ok(Expr::BinOp {
+                op: Operator::Add,
+                op_span: Span::new(4, 5),
+                lhs: Box::new(Expr::Call {
+                    name: "f".to_string(),
+                    name_span: Span::new(0, 1),
+                    args: vec![],
+                    span: Span::new(0, 3),
+                }),
+                rhs: Box::new(Expr::Call {
+                    name: "g".to_string(),
+                    name_span: Span::new(6, 7),
+                    args: vec![
+                        *num(1, 8),
+                        Expr::Neg {
+                            operand: Box::new(Expr::Var {
+                                name: "x".to_string(),
+                                span: Span::new(12, 13),
+                            }),
+                            op_span: Span::new(11, 12),
+                        },
+                    ],
+                    span: Span::new(6, 14),
+                }),
+            })
+        );
+
+        let options = EvalOptions::new()
+            .syntax(Syntax::letters().with_name("max"))
+            .max_depth(2);
+        let error = |source| {
+            Parser::new(source, &options)
+                .parse()
+                .map_err(|e| (e.kind, e.offset()))
+        };
+        assert!(error("max e max e1f, 2f").is_ok());
+        assert_eq!(
+            error("max e max e max ef f f"),
+            Err((ErrorKind::NestingTooDeep, 16))
+        );
+        assert_eq!(error("max e1,f"), Err((ErrorKind::MissingOperand, 7)));
+        assert_eq!(error("max e,1f"), Err((ErrorKind::MissingOperand, 5)));
+        assert_eq!(error("3, 4"), Err((ErrorKind::UnexpectedSeparator, 1)));
+        assert_eq!(error("3 e4f"), Err((ErrorKind::MissingOperator, 2)));
+    }
+
+    #[test]
+    fn print_round_trip() {
+        for source in [
+            "3 a e4 c 66f b 32",
+            "b10 a 50",
+            "e1 a e2 d 3ff c 4",
+            "x = y = 2 c z",
+            "k e1, bz, e2ff",
+            "x > 1 a 2 && !ok ? y : z < 0 ? 1 : 2",
+        ] {
+            let expr = parse(source, Strategy::LeftToRight);
+            assert_eq!(expr.to_string(), source);
+            assert_eq!(
+                parse(&expr.to_string(), Strategy::LeftToRight).to_string(),
+                source
+            );
+        }
+    }
+}
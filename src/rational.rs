@@ -0,0 +1,448 @@
+//! Exact fractions, so division never truncates
+//!
+//! Example:
+//! ```
+//! use parser_rs::{Environment, EvalOptions, Evaluator, Rational, Value};
+//!
+//! let evaluator = Evaluator::<Rational>::with_backend(EvalOptions::new());
+//! assert_eq!(evaluator.evaluate("1d3c3"), Ok(Rational::from(1)));
+//!
+//! let third = evaluator.evaluate("1d3").unwrap();
+//! assert_eq!(third.to_string(), "1/3");
+//! assert_eq!(format!("{third:.4}"), "0.3333");
+//!
+//! let mut env = Environment::new();
+//! let sum = evaluator.evaluate_value_in("0.1 a 0.2 == 0.3", &mut env);
+//! assert_eq!(sum, Ok(Value::Bool(true)));
+//! ```
+
+use crate::big_int::MAX_BITS;
+use crate::{ArithmeticMode, BigInt, ErrorKind, Number, Rounding};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Fraction of two [`BigInt`], always in lowest terms with a positive denominator
+///
+/// Never overflows in any [`ArithmeticMode`], except for powers too large for a `BigInt`.
+/// Printed as `numerator/denominator`, or as a decimal rounded half away from zero
+/// when a precision is given, like `{:.2}`
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Rational {
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+impl Rational {
+    /// `numerator / denominator` in lowest terms.
+    /// Returns `None` if `denominator` is zero
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        Rational::from_big_ints(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    /// Same as `new`, for a numerator and denominator of any size
+    pub fn from_big_ints(numerator: BigInt, denominator: BigInt) -> Option<Self> {
+        if denominator.is_zero() {
+            return None;
+        }
+        let divisor = gcd(&numerator, &denominator);
+        let (numerator, _) = numerator.div_rem(&divisor).ok()?;
+        let (denominator, _) = denominator.div_rem(&divisor).ok()?;
+        match denominator.is_negative() {
+            true => Some(Rational {
+                numerator: numerator.negated(),
+                denominator: denominator.negated(),
+            }),
+            false => Some(Rational {
+                numerator,
+                denominator,
+            }),
+        }
+    }
+
+    /// `numerator / denominator` in lowest terms, for a denominator known not to be zero
+    fn reduced(numerator: BigInt, denominator: BigInt) -> Self {
+        Rational::from_big_ints(numerator, denominator).expect("denominator is not zero")
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    /// Always positive
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == BigInt::from(1)
+    }
+
+    /// Largest integer not greater than this fraction
+    pub fn floor(&self) -> BigInt {
+        self.numerator
+            .div_floor(&self.denominator, ArithmeticMode::Checked)
+            .expect("denominator is not zero")
+    }
+
+    /// Integer part, rounding toward zero
+    pub fn trunc(&self) -> BigInt {
+        let (integer, _) = self
+            .numerator
+            .div_rem(&self.denominator)
+            .expect("denominator is not zero");
+        integer
+    }
+
+    fn reciprocal(&self) -> Result<Self, ErrorKind> {
+        match self.numerator.is_zero() {
+            true => Err(ErrorKind::DivisionByZero),
+            false => Ok(Rational::reduced(
+                self.denominator.clone(),
+                self.numerator.clone(),
+            )),
+        }
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(integer: i128) -> Self {
+        Rational::from(BigInt::from(integer))
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(integer: BigInt) -> Self {
+        Rational {
+            numerator: integer,
+            denominator: BigInt::from(1),
+        }
+    }
+}
+
+/// Greatest common divisor, `gcd(0, 0)` is 1 so it always divides
+fn gcd(lhs: &BigInt, rhs: &BigInt) -> BigInt {
+    let (mut lhs, mut rhs) = (lhs.abs(), rhs.abs());
+    while !rhs.is_zero() {
+        let (_, rest) = lhs.div_rem(&rhs).expect("divisor is not zero");
+        (lhs, rhs) = (rhs, rest);
+    }
+    match lhs.is_zero() {
+        true => BigInt::from(1),
+        false => lhs,
+    }
+}
+
+impl Ord for Rational {
+    /// Denominators are positive, so cross products compare like the fractions
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.numerator.times(&other.denominator);
+        let rhs = other.numerator.times(&self.denominator);
+        lhs.cmp(&rhs)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(precision) = f.precision() else {
+            return match self.is_integer() {
+                true => write!(f, "{}", self.numerator),
+                false => write!(f, "{}/{}", self.numerator, self.denominator),
+            };
+        };
+
+        // magnitude in units of the last digit, rounded half away from zero
+        let scale = BigInt::from(10)
+            .pow(
+                &BigInt::from(precision as i128),
+                ArithmeticMode::Checked,
+                Rounding::default(),
+                usize::MAX,
+            )
+            .map_err(|_| fmt::Error)?;
+        let scaled = self.numerator.abs().times(&scale);
+        let (mut units, rest) = scaled.div_rem(&self.denominator).map_err(|_| fmt::Error)?;
+        if rest.times(&BigInt::from(2)) >= self.denominator {
+            units = units.plus(&BigInt::from(1));
+        }
+
+        // no sign on a number rounded to zero
+        if self.numerator.is_negative() && !units.is_zero() {
+            write!(f, "-")?;
+        }
+        let digits = format!("{:0>width$}", units.to_string(), width = precision + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - precision);
+        write!(f, "{integer}")?;
+        if precision > 0 {
+            write!(f, ".{fraction}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Number for Rational {
+    const FRACTIONAL: bool = true;
+
+    fn zero() -> Self {
+        Rational::from(0)
+    }
+
+    /// Decimal literal is read exactly, `0.1` is `1/10`
+    fn from_literal(literal: &str, rounding: Rounding) -> Option<Self> {
+        let (mantissa, exponent) = match literal.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().ok()?),
+            None => (literal, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = BigInt::from_literal(&format!("{integer}{fraction}"), rounding)?;
+        let scale = exponent.checked_sub(i32::try_from(fraction.len()).ok()?)?;
+        let power = BigInt::from(10)
+            .pow(
+                &BigInt::from(i128::from(scale.unsigned_abs())),
+                ArithmeticMode::Checked,
+                rounding,
+                MAX_BITS,
+            )
+            .ok()?;
+        match scale < 0 {
+            true => Rational::from_big_ints(digits, power),
+            false => Some(Rational::from(digits.times(&power))),
+        }
+    }
+
+    fn add(&self, rhs: &Self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let numerator = self
+            .numerator
+            .times(&rhs.denominator)
+            .plus(&rhs.numerator.times(&self.denominator));
+        Ok(Rational::reduced(
+            numerator,
+            self.denominator.times(&rhs.denominator),
+        ))
+    }
+
+    fn sub(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        self.add(&rhs.neg(mode)?, mode)
+    }
+
+    fn mul(
+        &self,
+        rhs: &Self,
+        _mode: ArithmeticMode,
+        _rounding: Rounding,
+    ) -> Result<Self, ErrorKind> {
+        Ok(Rational::reduced(
+            self.numerator.times(&rhs.numerator),
+            self.denominator.times(&rhs.denominator),
+        ))
+    }
+
+    fn div(&self, rhs: &Self, mode: ArithmeticMode, rounding: Rounding) -> Result<Self, ErrorKind> {
+        self.mul(&rhs.reciprocal()?, mode, rounding)
+    }
+
+    /// What is left after taking out `rhs` a whole number of times toward zero
+    fn rem(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let times = Rational::from(self.div(rhs, mode, Rounding::default())?.trunc());
+        self.sub(&rhs.mul(&times, mode, Rounding::default())?, mode)
+    }
+
+    fn rem_euclid(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        let rest = self.rem(rhs, mode)?;
+        match rest.numerator.is_negative() {
+            true if rhs.numerator.is_negative() => rest.sub(rhs, mode),
+            true => rest.add(rhs, mode),
+            false => Ok(rest),
+        }
+    }
+
+    fn div_floor(&self, rhs: &Self, mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Ok(Rational::from(
+            self.div(rhs, mode, Rounding::default())?.floor(),
+        ))
+    }
+
+    /// Only integer exponents are supported, as other powers are rarely fractions
+    fn pow(
+        &self,
+        rhs: &Self,
+        mode: ArithmeticMode,
+        rounding: Rounding,
+        max_bits: usize,
+    ) -> Result<Self, ErrorKind> {
+        if !rhs.is_integer() {
+            return Err(ErrorKind::UnsupportedOperation);
+        }
+        let base = match rhs.numerator.is_negative() {
+            true => self.reciprocal()?,
+            false => self.clone(),
+        };
+        let exponent = rhs.numerator.abs();
+        // powers of coprime numbers are coprime
+        Ok(Rational {
+            numerator: base.numerator.pow(&exponent, mode, rounding, max_bits)?,
+            denominator: base.denominator.pow(&exponent, mode, rounding, max_bits)?,
+        })
+    }
+
+    fn neg(&self, _mode: ArithmeticMode) -> Result<Self, ErrorKind> {
+        Ok(Rational {
+            numerator: self.numerator.negated(),
+            denominator: self.denominator.clone(),
+        })
+    }
+
+    fn to_radix(&self, radix: u32) -> Option<(bool, String)> {
+        match self.is_integer() {
+            true => self.numerator.to_radix(radix),
+            false => None,
+        }
+    }
+
+    fn to_fraction(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn normalized() {
+        assert_eq!(ratio(6, -4), ratio(-3, 2));
+        assert_eq!(ratio(-3, 2).numerator(), &BigInt::from(-3));
+        assert_eq!(ratio(-3, 2).denominator(), &BigInt::from(2));
+        assert_eq!(ratio(0, -7), Rational::from(0));
+        assert_eq!(Rational::new(1, 0), None);
+        assert_eq!(
+            Rational::new(i128::MIN, -1).map(|ratio| ratio.to_string()),
+            Some(i128::MIN.unsigned_abs().to_string())
+        );
+        assert_eq!(ratio(-7, 2).floor(), BigInt::from(-4));
+        assert_eq!(ratio(-7, 2).trunc(), BigInt::from(-3));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mode = ArithmeticMode::Checked;
+        let rounding = Rounding::default();
+        assert_eq!(ratio(1, 6).add(&ratio(1, 3), mode), Ok(ratio(1, 2)));
+        assert_eq!(ratio(1, 6).sub(&ratio(1, 3), mode), Ok(ratio(-1, 6)));
+        assert_eq!(
+            ratio(2, 3).mul(&ratio(9, 4), mode, rounding),
+            Ok(ratio(3, 2))
+        );
+        assert_eq!(
+            ratio(2, 3).div(&ratio(-4, 9), mode, rounding),
+            Ok(ratio(-3, 2))
+        );
+        assert_eq!(
+            ratio(2, 3).div(&Rational::from(0), mode, rounding),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(ratio(-7, 2).rem(&Rational::from(2), mode), Ok(ratio(-3, 2)));
+        assert_eq!(
+            ratio(-7, 2).rem_euclid(&Rational::from(-2), mode),
+            Ok(ratio(1, 2))
+        );
+        assert_eq!(
+            ratio(-7, 2).div_floor(&Rational::from(1), mode),
+            Ok(Rational::from(-4))
+        );
+        assert_eq!(
+            ratio(2, 3).pow(&Rational::from(-2), mode, rounding, MAX_BITS),
+            Ok(ratio(9, 4))
+        );
+        assert_eq!(
+            ratio(2, 3).pow(&ratio(1, 2), mode, rounding, MAX_BITS),
+            Err(ErrorKind::UnsupportedOperation)
+        );
+        assert_eq!(
+            Rational::from(-1).pow(&Rational::from(i128::MAX), mode, rounding, MAX_BITS),
+            Ok(Rational::from(-1))
+        );
+        // no bound on numerator and denominator
+        let sum = Rational::from(i128::MAX).add(&ratio(1, 2), mode).unwrap();
+        assert_eq!(sum.to_string(), format!("{}/2", u128::MAX));
+        let two = Rational::from(2);
+        let tiny = two
+            .pow(&Rational::from(-200), mode, rounding, MAX_BITS)
+            .unwrap();
+        let power = BigInt::from(2).pow(&BigInt::from(200), mode, rounding, MAX_BITS);
+        assert_eq!(tiny.to_string(), format!("1/{}", power.unwrap()));
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(ratio(1, 3) < ratio(1, 2));
+        assert!(ratio(-1, 2) < ratio(-1, 3));
+        assert!(ratio(7, 2) > Rational::from(3));
+        // products of these do not fit an `i128`
+        let big = i128::MAX;
+        assert!(ratio(big - 2, big - 1) < ratio(big - 1, big));
+        assert_eq!(
+            ratio(big - 1, big).cmp(&ratio(big - 1, big)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(
+            Rational::from_literal("12", Rounding::default()),
+            Some(Rational::from(12))
+        );
+        assert_eq!(
+            Rational::from_literal("0.1", Rounding::default()),
+            Some(ratio(1, 10))
+        );
+        assert_eq!(
+            Rational::from_literal("2.50", Rounding::default()),
+            Some(ratio(5, 2))
+        );
+        assert_eq!(
+            Rational::from_literal("1.5e3", Rounding::default()),
+            Some(Rational::from(1500))
+        );
+        assert_eq!(
+            Rational::from_literal("25E-3", Rounding::default()),
+            Some(ratio(1, 40))
+        );
+        assert_eq!(
+            Rational::from_literal("1e-50", Rounding::default()).map(|tiny| tiny.to_string()),
+            Some(format!("1/1{}", "0".repeat(50)))
+        );
+        assert_eq!(
+            Rational::from_literal("1e39", Rounding::default()).map(|huge| huge.to_string()),
+            Some(format!("1{}", "0".repeat(39)))
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ratio(-3, 2).to_string(), "-3/2");
+        assert_eq!(Rational::from(4).to_string(), "4");
+        assert_eq!(format!("{:.3}", ratio(2, 3)), "0.667");
+        assert_eq!(format!("{:.2}", ratio(-1, 8)), "-0.13");
+        assert_eq!(format!("{:.1}", ratio(999, 1000)), "1.0");
+        assert_eq!(format!("{:.0}", ratio(5, 2)), "3");
+        assert_eq!(format!("{:.2}", Rational::from(7)), "7.00");
+        assert_eq!(format!("{:.2}", ratio(-1, 1000)), "0.00");
+        assert_eq!(format!("{:.0}", ratio(-1, 3)), "0");
+        assert_eq!(format!("{:.2}", ratio(-1, 200)), "-0.01");
+        let tiny = ratio(1, i128::MAX);
+        assert_eq!(format!("{tiny:.40}"), format!("0.{}59", "0".repeat(38)));
+        assert_eq!(format!("{:.3}", ratio(-1, 2000)), "-0.001");
+        assert_eq!(format!("{:.1}", ratio(-19, 2)), "-9.5");
+    }
+}
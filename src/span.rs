@@ -0,0 +1,44 @@
+use std::ops::Range;
+
+/// Byte range `start..end` of the expression a piece of syntax came from
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Empty span pointing right before byte `offset`
+    /// Used for errors at end of input
+    pub fn empty(offset: usize) -> Self {
+        Span::new(offset, offset)
+    }
+
+    /// Span covering character `ch` starting at byte `offset`
+    pub fn of_char(offset: usize, ch: char) -> Self {
+        Span::new(offset, offset + ch.len_utf8())
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Smallest span covering both `self` and `other`
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
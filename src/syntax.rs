@@ -0,0 +1,292 @@
+//! Symbols an expression is written with
+//!
+//! Example:
+//! ```
+//! use parser_rs::{compute, EvalOptions, Evaluator, Operator, Symbol, Syntax};
+//!
+//! // letters are used by default
+//! assert_eq!(compute("3 a 2 c 4"), Ok(20));
+//!
+//! let syntax = Syntax::standard().with_symbol("div", Symbol::Op(Operator::Div));
+//! let evaluator = Evaluator::with_options(EvalOptions::new().syntax(syntax));
+//! assert_eq!(evaluator.evaluate("(3 + 2) * 4"), Ok(20));
+//! assert_eq!(evaluator.evaluate("21 div 5"), Ok(4));
+//! ```
+
+use crate::{Operator, UnaryOperator};
+
+/// Meaning of a symbol in the expression
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Symbol {
+    Op(Operator),
+    Unary(UnaryOperator),
+    LParen,
+    RParen,
+    /// Separates independent statements
+    EndStatement,
+    /// Assigns value to a variable
+    Assign,
+    /// Separates arguments of a function call
+    ArgSeparator,
+    /// Separates condition and value if it is true, like `?`
+    Then,
+    /// Separates values if condition is true and false, like `:`
+    Else,
+}
+
+/// Character that can continue a variable name
+pub(crate) fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Character that can continue a variable name but not start a number
+fn continues_name(ch: char) -> bool {
+    is_word_char(ch) && !ch.is_ascii_digit()
+}
+
+/// Symbol made of several word characters, like `div`.
+/// Such symbol is only recognized as a whole word
+fn is_word(text: &str) -> bool {
+    text.chars().count() > 1 && text.chars().all(is_word_char)
+}
+
+/// Table of symbols mapped to what they mean
+///
+/// Several symbols may mean the same thing.
+/// The one registered first is used when printing that meaning back.
+///
+/// Names, like those of functions, can be reserved to always be read
+/// as a whole word, even if made of symbols like `abs` in letter syntax.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Syntax {
+    symbols: Vec<(String, Symbol)>,
+    names: Vec<String>,
+}
+
+impl Default for Syntax {
+    fn default() -> Self {
+        Syntax::letters()
+    }
+}
+
+impl Syntax {
+    /// Syntax without any symbol
+    pub fn empty() -> Self {
+        Syntax {
+            symbols: vec![],
+            names: vec![],
+        }
+    }
+
+    /// Operators +, -, *, / represented by a, b, c, d respectively.
+    /// Open and close parenthesis are represented by e, f respectively.
+    /// Remainder, Euclidean modulo, exponent and floor division
+    /// are represented by g, h, i, j respectively.
+    /// Statements are separated by ; and variables are assigned with =
+    /// Function arguments are separated by ,
+    /// Comparisons, logical operators and conditions are same as [`Syntax::standard`]
+    ///
+    /// Since letters a to j are operators,
+    /// variable names can not contain them
+    /// and hex digits are written in uppercase, like `0x1F`.
+    /// Binary literals take the uppercase `0B` prefix, as `0b1` is `0 - 1`
+    pub fn letters() -> Self {
+        Syntax::empty()
+            .with_symbol("a", Symbol::Op(Operator::Add))
+            .with_symbol("b", Symbol::Op(Operator::Sub))
+            .with_symbol("c", Symbol::Op(Operator::Mul))
+            .with_symbol("d", Symbol::Op(Operator::Div))
+            .with_symbol("e", Symbol::LParen)
+            .with_symbol("f", Symbol::RParen)
+            .with_symbol("g", Symbol::Op(Operator::Rem))
+            .with_symbol("h", Symbol::Op(Operator::Mod))
+            .with_symbol("i", Symbol::Op(Operator::Pow))
+            .with_symbol("j", Symbol::Op(Operator::FloorDiv))
+            .with_symbol(";", Symbol::EndStatement)
+            .with_symbol("=", Symbol::Assign)
+            .with_symbol(",", Symbol::ArgSeparator)
+            .with_logic()
+    }
+
+    /// Conventional `+ - * / ( )` with `%` remainder, `mod` Euclidean modulo,
+    /// `**` exponent and `//` floor division.
+    /// Statements are separated by `;`,
+    /// variables assigned with `=` and function arguments separated by `,`.
+    /// Comparisons are `< <= > >= == !=`, logical operators `&& || !`
+    /// and conditions are written as `condition ? value : other`
+    pub fn standard() -> Self {
+        Syntax::empty()
+            .with_symbol("+", Symbol::Op(Operator::Add))
+            .with_symbol("-", Symbol::Op(Operator::Sub))
+            .with_symbol("*", Symbol::Op(Operator::Mul))
+            .with_symbol("/", Symbol::Op(Operator::Div))
+            .with_symbol("(", Symbol::LParen)
+            .with_symbol(")", Symbol::RParen)
+            .with_symbol("%", Symbol::Op(Operator::Rem))
+            .with_symbol("mod", Symbol::Op(Operator::Mod))
+            .with_symbol("**", Symbol::Op(Operator::Pow))
+            .with_symbol("//", Symbol::Op(Operator::FloorDiv))
+            .with_symbol(";", Symbol::EndStatement)
+            .with_symbol("=", Symbol::Assign)
+            .with_symbol(",", Symbol::ArgSeparator)
+            .with_logic()
+    }
+
+    /// Comparisons, logical operators and conditions shared by both presets
+    fn with_logic(self) -> Self {
+        self.with_symbol("<", Symbol::Op(Operator::Lt))
+            .with_symbol("<=", Symbol::Op(Operator::Le))
+            .with_symbol(">", Symbol::Op(Operator::Gt))
+            .with_symbol(">=", Symbol::Op(Operator::Ge))
+            .with_symbol("==", Symbol::Op(Operator::Eq))
+            .with_symbol("!=", Symbol::Op(Operator::Ne))
+            .with_symbol("&&", Symbol::Op(Operator::And))
+            .with_symbol("||", Symbol::Op(Operator::Or))
+            .with_symbol("!", Symbol::Unary(UnaryOperator::Not))
+            .with_symbol("?", Symbol::Then)
+            .with_symbol(":", Symbol::Else)
+    }
+
+    /// Add bitwise operators `& | ^ ~` and shifts `<< >>`
+    pub fn with_bitwise(self) -> Self {
+        self.with_symbol("&", Symbol::Op(Operator::BitAnd))
+            .with_symbol("|", Symbol::Op(Operator::BitOr))
+            .with_symbol("^", Symbol::Op(Operator::BitXor))
+            .with_symbol("~", Symbol::Unary(UnaryOperator::BitNot))
+            .with_symbol("<<", Symbol::Op(Operator::Shl))
+            .with_symbol(">>", Symbol::Op(Operator::Shr))
+    }
+
+    /// Make `text` mean `symbol` in expressions
+    ///
+    /// Panics if `text` is empty, contains whitespace or starts with a digit,
+    /// as such symbol could never be told apart from the rest of the expression
+    pub fn with_symbol(mut self, text: impl Into<String>, symbol: Symbol) -> Self {
+        let text = text.into();
+        assert!(
+            text.chars().next().is_some_and(|ch| !ch.is_ascii_digit())
+                && !text.contains(char::is_whitespace),
+            "Invalid symbol: {text:?}"
+        );
+
+        self.symbols.retain(|(existing, _)| *existing != text);
+        self.symbols.push((text, symbol));
+        self
+    }
+
+    /// Always read `name` as a single word
+    ///
+    /// Panics if `name` is not made of letters, digits and `_` or starts with a digit
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        assert!(
+            name.chars().next().is_some_and(|ch| !ch.is_ascii_digit())
+                && name.chars().all(is_word_char),
+            "Invalid name: {name:?}"
+        );
+
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        }
+        self
+    }
+
+    /// Length in bytes of the reserved name making up a whole word at the start of `input`.
+    /// The word may end at a symbol that breaks words, like `e` in `abse1f` of letter syntax
+    pub fn match_name(&self, input: &str) -> Option<usize> {
+        self.names
+            .iter()
+            .filter(|name| {
+                let rest = input.strip_prefix(name.as_str());
+                rest.is_some_and(|rest| !rest.starts_with(is_word_char) || self.breaks_word(rest))
+            })
+            .map(String::len)
+            .max()
+    }
+
+    /// Text printed for `symbol`, if this syntax has one
+    pub fn text_of(&self, symbol: Symbol) -> Option<&str> {
+        self.symbols
+            .iter()
+            .find(|(_, existing)| *existing == symbol)
+            .map(|(text, _)| text.as_str())
+    }
+
+    /// Symbol at the very start of `input`
+    /// When several symbols match, the longest one wins.
+    /// Word symbols like `div` do not match start of a longer word like `divisor`,
+    /// but may be followed by a number like in `div2`
+    ///
+    /// Returns the symbol and its length in bytes
+    pub fn match_symbol(&self, input: &str) -> Option<(Symbol, usize)> {
+        self.symbols
+            .iter()
+            .filter(|(text, _)| {
+                input.starts_with(text.as_str())
+                    && !(is_word(text) && input[text.len()..].starts_with(continues_name))
+            })
+            .max_by_key(|(text, _)| text.len())
+            .map(|(text, symbol)| (*symbol, text.len()))
+    }
+
+    /// Whether a symbol starting at the very start of `input`
+    /// ends a variable name running into it.
+    /// Word symbols never do, they are part of the name instead
+    pub fn breaks_word(&self, input: &str) -> bool {
+        self.symbols
+            .iter()
+            .any(|(text, _)| !is_word(text) && input.starts_with(text.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_symbol_wins() {
+        let syntax = Syntax::standard()
+            .with_symbol("**", Symbol::Op(Operator::Mul))
+            .with_symbol("div", Symbol::Op(Operator::Div));
+
+        assert_eq!(
+            syntax.match_symbol("** 2"),
+            Some((Symbol::Op(Operator::Mul), 2))
+        );
+        assert_eq!(
+            syntax.match_symbol("* 2"),
+            Some((Symbol::Op(Operator::Mul), 1))
+        );
+        assert_eq!(
+            syntax.match_symbol("div 2"),
+            Some((Symbol::Op(Operator::Div), 3))
+        );
+        assert_eq!(syntax.match_symbol("di"), None);
+        assert_eq!(syntax.match_symbol("divisor"), None);
+    }
+
+    #[test]
+    fn first_symbol_is_printed() {
+        let syntax = Syntax::letters().with_symbol("+", Symbol::Op(Operator::Add));
+        assert_eq!(syntax.text_of(Symbol::Op(Operator::Add)), Some("a"));
+        assert_eq!(syntax.text_of(Symbol::LParen), Some("e"));
+        assert_eq!(Syntax::empty().text_of(Symbol::LParen), None);
+    }
+
+    #[test]
+    fn reserved_names() {
+        let syntax = Syntax::letters().with_name("abs").with_name("abs_b");
+        assert_eq!(syntax.match_name("abs e1f"), Some(3));
+        assert_eq!(syntax.match_name("abs_b"), Some(5));
+        assert_eq!(syntax.match_name("absx"), None);
+        assert_eq!(syntax.match_name("abse1f"), Some(3));
+        assert_eq!(syntax.match_name("abs_be1f"), Some(5));
+        assert_eq!(syntax.match_name("a 1"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid symbol")]
+    fn symbol_can_not_start_with_digit() {
+        let _ = Syntax::standard().with_symbol("1x", Symbol::Op(Operator::Mul));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{ErrorKind, EvalError, Expr, Number};
+use std::fmt;
+
+/// Kind of a [`Value`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Type {
+    Number,
+    Bool,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Number => write!(f, "number"),
+            Type::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+/// Result of an expression
+///
+/// Arithmetic produces numbers, while comparisons and logical operators produce bools.
+/// Using one where the other is expected fails with `ErrorKind::TypeMismatch`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Value<N = i128> {
+    Number(N),
+    Bool(bool),
+}
+
+impl<N> Value<N> {
+    pub fn type_of(&self) -> Type {
+        match self {
+            Value::Number(_) => Type::Number,
+            Value::Bool(_) => Type::Bool,
+        }
+    }
+
+    pub fn as_number(self) -> Option<N> {
+        match self {
+            Value::Number(number) => Some(number),
+            Value::Bool(_) => None,
+        }
+    }
+
+    pub fn as_bool(self) -> Option<bool> {
+        match self {
+            Value::Bool(bool) => Some(bool),
+            Value::Number(_) => None,
+        }
+    }
+
+    /// Number held by this value,
+    /// or a type error at expression `from` the value came from
+    pub(crate) fn number(self, from: &Expr<N>) -> Result<N, EvalError> {
+        match self {
+            Value::Number(number) => Ok(number),
+            Value::Bool(_) => Err(self.mismatch(Type::Number, from)),
+        }
+    }
+
+    /// Bool held by this value,
+    /// or a type error at expression `from` the value came from
+    pub(crate) fn bool(self, from: &Expr<N>) -> Result<bool, EvalError> {
+        match self {
+            Value::Bool(bool) => Ok(bool),
+            Value::Number(_) => Err(self.mismatch(Type::Bool, from)),
+        }
+    }
+
+    fn mismatch(&self, expected: Type, from: &Expr<N>) -> EvalError {
+        let kind = ErrorKind::TypeMismatch {
+            expected,
+            found: self.type_of(),
+        };
+        EvalError::new(kind, from.span())
+    }
+}
+
+impl<N: Number> From<N> for Value<N> {
+    fn from(number: N) -> Self {
+        Value::Number(number)
+    }
+}
+
+impl<N> From<bool> for Value<N> {
+    fn from(bool: bool) -> Self {
+        Value::Bool(bool)
+    }
+}
+
+impl<N: fmt::Display> fmt::Display for Value<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) => write!(f, "{number}"),
+            Value::Bool(bool) => write!(f, "{bool}"),
+        }
+    }
+}